@@ -0,0 +1,120 @@
+use crate::body::Body;
+use crate::quadtree::QuadTree;
+use crate::universe::Universe;
+use cgmath::Vector2;
+
+/// Leapfrog universe with accelerations approximated by the Barnes-Hut algorithm in O(N log N) time.
+pub struct BarnesHutUniverse {
+    bodies: Vec<Body>,
+    opening_angle: f64,
+    acceleration_buffer: Vec<Vector2<f64>>, // Accelerations of the current positions.
+    tree: QuadTree,                         // Buffer.
+}
+
+fn compute_accelerations(tree: &mut QuadTree, bodies: &[Body], opening_angle: f64, accelerations: &mut [Vector2<f64>]) {
+    tree.build(bodies);
+
+    for (i, acceleration) in accelerations.iter_mut().enumerate() {
+        *acceleration = tree.compute_acceleration(bodies, i, opening_angle);
+    }
+}
+
+impl BarnesHutUniverse {
+    /// Smaller opening angles give more accurate accelerations with more computations, and 0 gives the direct sum.
+    pub fn new(bodies: &[Body], opening_angle: f64) -> BarnesHutUniverse {
+        let mut tree = QuadTree::new();
+        let mut acceleration_buffer = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        compute_accelerations(&mut tree, bodies, opening_angle, &mut acceleration_buffer);
+
+        BarnesHutUniverse {
+            bodies: bodies.to_vec(),
+            opening_angle,
+            acceleration_buffer,
+            tree,
+        }
+    }
+}
+
+impl Universe for BarnesHutUniverse {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+            body.position += body.velocity * time;
+        }
+
+        compute_accelerations(
+            &mut self.tree,
+            &self.bodies,
+            self.opening_angle,
+            &mut self.acceleration_buffer,
+        );
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_accelerations, BarnesHutUniverse};
+    use crate::forces;
+    use crate::quadtree::QuadTree;
+    use crate::test_utilities::{check_trajectory, get_random_bodies, get_taylor_bodies};
+    use crate::universe::Universe;
+    use cgmath::{InnerSpace, Vector2};
+
+    fn check_accelerations(opening_angle: f64, tolerance: f64) {
+        let bodies = get_random_bodies(1000);
+        let mut expected_accelerations = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+        let mut accelerations = expected_accelerations.clone();
+
+        forces::compute_accelerations(&bodies, &mut expected_accelerations);
+        compute_accelerations(&mut QuadTree::new(), &bodies, opening_angle, &mut accelerations);
+
+        // Relative errors of single accelerations can be large where the forces almost cancel, so the error is
+        // relative to the overall magnitude of the accelerations instead.
+
+        let error = accelerations
+            .iter()
+            .zip(&expected_accelerations)
+            .map(|(a, e)| (a - e).magnitude2())
+            .sum::<f64>();
+
+        let magnitude = expected_accelerations.iter().map(|a| a.magnitude2()).sum::<f64>();
+
+        assert!((error / magnitude).sqrt() < tolerance);
+    }
+
+    #[test]
+    fn accelerations() {
+        check_accelerations(0.0, 1.0e-12);
+        check_accelerations(0.5, 1.0e-3);
+        check_accelerations(1.0, 1.0e-2);
+    }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(BarnesHutUniverse::new(&get_taylor_bodies(), 0.0), 0.001, 1000, 1.0e-15);
+    }
+
+    #[test]
+    fn charges() {
+        // Without Coulomb forces, the energy ignores charges as well.
+
+        let bodies = get_taylor_bodies();
+        let charged_bodies = bodies.iter().map(|b| b.clone().with_charge(b.mass)).collect::<Vec<_>>();
+
+        assert_eq!(
+            BarnesHutUniverse::new(&charged_bodies, 0.5).get_energy(),
+            BarnesHutUniverse::new(&bodies, 0.5).get_energy()
+        );
+    }
+}
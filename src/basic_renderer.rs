@@ -2,9 +2,12 @@ use crate::configuration::Color;
 use crate::renderer::Renderer;
 use cgmath::Vector2;
 use itertools::izip;
-use wasm_bindgen::JsValue;
 use web_sys::CanvasRenderingContext2d;
 
+// Test particles are drawn as faint dots on every frame, which add up to faint trails.
+const TEST_PARTICLE_COLOR: &str = "rgba(255, 255, 255, 0.125)";
+const TEST_PARTICLE_SIZE: f64 = 1.0;
+
 pub struct BasicRenderer {
     canvas_context: CanvasRenderingContext2d,
     body_colors: Vec<String>,
@@ -20,7 +23,7 @@ impl BasicRenderer {
         trail_widths: Vec<f64>,
     ) -> BasicRenderer {
         canvas_context.set_global_composite_operation("screen").unwrap();
-        canvas_context.set_fill_style(&JsValue::from_str("black"));
+        canvas_context.set_fill_style_str("black");
         canvas_context.fill_rect(-width * 0.5, -height * 0.5, width, height);
 
         BasicRenderer {
@@ -35,7 +38,7 @@ impl Renderer for BasicRenderer {
     fn render(&mut self, position_histories: &[Vec<Vector2<f64>>]) {
         for (position_history, color, trail_width) in izip!(position_histories, &self.body_colors, &self.trail_widths) {
             if position_history.len() > 1 {
-                self.canvas_context.set_stroke_style(&JsValue::from_str(&color));
+                self.canvas_context.set_stroke_style_str(color);
                 self.canvas_context.set_line_width(*trail_width);
 
                 self.canvas_context.begin_path();
@@ -44,12 +47,47 @@ impl Renderer for BasicRenderer {
 
                 self.canvas_context.move_to(first_position.x, first_position.y);
 
+                // A non-finite position breaks the trail, which continues from the next position.
+
+                let mut broken = false;
+
                 for position in rest_positions {
-                    self.canvas_context.line_to(position.x, position.y);
+                    if !position.x.is_finite() {
+                        broken = true;
+                    } else if broken {
+                        self.canvas_context.move_to(position.x, position.y);
+
+                        broken = false;
+                    } else {
+                        self.canvas_context.line_to(position.x, position.y);
+                    }
                 }
 
                 self.canvas_context.stroke();
             }
         }
     }
+
+    fn remove_body(&mut self, index: usize) {
+        self.body_colors.remove(index);
+        self.trail_widths.remove(index);
+    }
+
+    fn render_test_particles(&mut self, positions: &[Vector2<f64>]) {
+        let half_size = TEST_PARTICLE_SIZE * 0.5;
+
+        self.canvas_context.set_fill_style_str(TEST_PARTICLE_COLOR);
+        self.canvas_context.begin_path();
+
+        for position in positions {
+            self.canvas_context.rect(
+                position.x - half_size,
+                position.y - half_size,
+                TEST_PARTICLE_SIZE,
+                TEST_PARTICLE_SIZE,
+            );
+        }
+
+        self.canvas_context.fill();
+    }
 }
@@ -1,6 +1,7 @@
 use crate::renderer::Renderer;
 use crate::scheduler::Scheduler;
 use crate::universe::Universe;
+use crate::utilities::minimum_image;
 use cgmath::{InnerSpace, Vector2};
 
 pub struct BasicScheduler {
@@ -10,6 +11,7 @@ pub struct BasicScheduler {
     last_timestamp: f64,                        // State.
     start_time: f64,                            // State.
     position_histories: Vec<Vec<Vector2<f64>>>, // Shared Buffer.
+    test_particle_positions: Vec<Vector2<f64>>, // Buffer.
 }
 
 impl BasicScheduler {
@@ -21,6 +23,27 @@ impl BasicScheduler {
             last_timestamp: 0.0,
             start_time: 0.0,
             position_histories: universe.get_bodies().iter().map(|b| vec![b.position]).collect(),
+            test_particle_positions: Vec::new(),
+        }
+    }
+
+    /// Plays the simulation backward in time, which retraces the past of the configuration.
+    pub fn with_backward_playback(self) -> BasicScheduler {
+        BasicScheduler {
+            step_size: -self.step_size,
+            ..self
+        }
+    }
+
+    // Renders the histories, and discards all but their last positions.
+    fn flush<R: Renderer>(&mut self, renderer: &mut R) {
+        renderer.render(&self.position_histories);
+
+        for position_history in &mut self.position_histories {
+            let last_index = position_history.len() - 1;
+
+            position_history.swap(0, last_index);
+            position_history.truncate(1);
         }
     }
 }
@@ -46,31 +69,99 @@ impl Scheduler for BasicScheduler {
         for _ in self.sampled..target_samples {
             universe.advance(self.step_size);
 
+            // Finish the trails of removed bodies before forgetting them.
+
+            if !universe.get_removed_bodies().is_empty() {
+                self.flush(renderer);
+
+                for &index in universe.get_removed_bodies().iter().rev() {
+                    self.position_histories.remove(index);
+                    renderer.remove_body(index);
+                }
+            }
+
+            let period = universe.get_period();
+
             for (position_history, position) in self
                 .position_histories
                 .iter_mut()
                 .zip(universe.get_bodies().iter().map(|b| b.position))
             {
-                if (position - position_history.last().unwrap()).magnitude2() >= 1.0 {
-                    position_history.push(position);
+                let last_position = *position_history.last().unwrap();
+                let displacement = position - last_position;
+
+                match period.map(|period| minimum_image(displacement, period)) {
+                    // Wrapped around a periodic box, so the trail leaves through one edge and enters through the
+                    // opposite one, with a gap in between.
+                    Some(shortest_displacement) if shortest_displacement != displacement => {
+                        position_history.push(last_position + shortest_displacement);
+                        position_history.push(Vector2::new(f64::NAN, f64::NAN));
+                        position_history.push(position - shortest_displacement);
+                        position_history.push(position);
+                    }
+                    _ => {
+                        if displacement.magnitude2() >= 1.0 {
+                            position_history.push(position);
+                        }
+                    }
                 }
             }
         }
 
         // Do actual drawings.
 
-        renderer.render(&self.position_histories);
+        self.flush(renderer);
 
-        // Discard old histories.
+        if !universe.get_test_particles().is_empty() {
+            self.test_particle_positions.clear();
 
-        for position_history in &mut self.position_histories {
-            let last_index = position_history.len() - 1;
+            self.test_particle_positions
+                .extend(universe.get_test_particles().iter().map(|p| p.position));
 
-            position_history.swap(0, last_index);
-            position_history.truncate(1);
+            renderer.render_test_particles(&self.test_particle_positions);
         }
 
         self.sampled = target_samples;
         self.last_timestamp = timestamp;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BasicScheduler;
+    use crate::leapfrog_universe::LeapfrogUniverse;
+    use crate::renderer::Renderer;
+    use crate::scheduler::Scheduler;
+    use crate::test_utilities::get_taylor_bodies;
+    use crate::universe::Universe;
+    use cgmath::Vector2;
+
+    struct NullRenderer;
+
+    impl Renderer for NullRenderer {
+        fn render(&mut self, _position_histories: &[Vec<Vector2<f64>>]) {}
+    }
+
+    #[test]
+    fn backward_playback() {
+        // Playing backward takes the same steps as advancing the universe by negative times.
+
+        let mut universe = LeapfrogUniverse::new(&get_taylor_bodies());
+        let mut expected_universe = LeapfrogUniverse::new(&get_taylor_bodies());
+        let mut scheduler = BasicScheduler::new(1000.0, &universe).with_backward_playback();
+
+        scheduler.advance(100.0, &mut universe, &mut NullRenderer);
+
+        for _ in 0..100 {
+            expected_universe.advance(-0.001);
+        }
+
+        assert_eq!(universe.get_time(), expected_universe.get_time());
+        assert!(universe.get_time() < 0.0);
+
+        for (body, expected_body) in universe.get_bodies().iter().zip(expected_universe.get_bodies()) {
+            assert_eq!(body.position, expected_body.position);
+            assert_eq!(body.velocity, expected_body.velocity);
+        }
+    }
+}
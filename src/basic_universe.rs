@@ -1,68 +1,266 @@
 use crate::body::Body;
+use crate::collision::{drift_with_bounces, merge_overlapping_bodies, Collision};
+use crate::drag::Drag;
+use crate::external_field::ExternalField;
+use crate::force_law::ForceLaw;
+use crate::forces::{
+    add_drag_accelerations, add_external_accelerations, add_post_newtonian_accelerations,
+    add_radiation_reaction_accelerations, compute_pairwise_accelerations, compute_test_particle_accelerations,
+};
+use crate::softening::Softening;
+use crate::test_particle::TestParticle;
 use crate::universe::Universe;
-use cgmath::{InnerSpace, Vector2};
-use itertools::Itertools;
+use cgmath::Vector2;
 
 pub struct BasicUniverse {
     bodies: Vec<Body>,
+    force_law: ForceLaw,
+    softening: Softening,
+    collision: Collision,
+    test_particles: Vec<TestParticle>,
+    external_fields: Vec<ExternalField>,
+    drags: Vec<Drag>,
+    speed_of_light: Option<f64>,     // Enables post-Newtonian corrections.
+    radiation_reaction: Option<f64>, // Speed of light of the radiation reaction of gravitational waves.
+    time: f64,                       // State.
+    removed_bodies: Vec<usize>,      // State.
+    radiated_energy: f64,            // Statistics.
+    dissipated_energy: f64,          // Statistics.
     acceleration_buffer: Vec<Vector2<f64>>,
+    test_particle_acceleration_buffer: Vec<Vector2<f64>>,
+    post_newtonian_buffer: Vec<(Vector2<f64>, f64)>,
 }
 
 impl BasicUniverse {
     pub fn new(bodies: &[Body]) -> BasicUniverse {
         BasicUniverse {
             bodies: bodies.to_vec(),
+            force_law: ForceLaw::InverseSquare,
+            softening: Softening::None,
+            collision: Collision::None,
+            test_particles: Vec::new(),
+            external_fields: Vec::new(),
+            drags: Vec::new(),
+            speed_of_light: None,
+            radiation_reaction: None,
+            time: 0.0,
+            removed_bodies: Vec::new(),
+            radiated_energy: 0.0,
+            dissipated_energy: 0.0,
             acceleration_buffer: vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()],
+            test_particle_acceleration_buffer: Vec::new(),
+            post_newtonian_buffer: Vec::new(),
         }
     }
-}
 
-fn pow_negative_one_half(x: f64) -> f64 {
-    (x.sqrt() * x).recip()
+    pub fn with_test_particles(self, test_particles: &[TestParticle]) -> BasicUniverse {
+        BasicUniverse {
+            test_particles: test_particles.to_vec(),
+            test_particle_acceleration_buffer: vec![Vector2 { x: 0.0, y: 0.0 }; test_particles.len()],
+            ..self
+        }
+    }
+
+    pub fn with_external_fields(self, external_fields: &[ExternalField]) -> BasicUniverse {
+        BasicUniverse {
+            external_fields: external_fields.to_vec(),
+            ..self
+        }
+    }
+
+    /// Adds drags acting on bodies and test particles. The dissipated energy is reported separately from the energy.
+    pub fn with_drags(self, drags: &[Drag]) -> BasicUniverse {
+        BasicUniverse {
+            drags: drags.to_vec(),
+            ..self
+        }
+    }
+
+    /// Adds the first post-Newtonian corrections of general relativity with the given speed of light, which make orbits
+    /// precess. They only apply to gravity between bodies, and the energy ignores them.
+    pub fn with_speed_of_light(self, speed_of_light: f64) -> BasicUniverse {
+        BasicUniverse {
+            speed_of_light: Some(speed_of_light),
+            ..self
+        }
+    }
+
+    /// Adds the radiation reaction of gravitational waves with the given speed of light, so binaries spiral in. It is
+    /// independent of `with_speed_of_light`, and the radiated energy is reported separately from the energy.
+    pub fn with_radiation_reaction(self, speed_of_light: f64) -> BasicUniverse {
+        BasicUniverse {
+            radiation_reaction: Some(speed_of_light),
+            ..self
+        }
+    }
+
+    /// Lets bodies collide, which they pass through by default.
+    pub fn with_collision(self, collision: Collision) -> BasicUniverse {
+        BasicUniverse { collision, ..self }
+    }
+
+    /// Smooths the inverse square law at short distances, which is unsoftened by default.
+    pub fn with_softening(self, softening: Softening) -> BasicUniverse {
+        BasicUniverse { softening, ..self }
+    }
+
+    /// Replaces the default inverse square law. The softening only applies to the inverse square law.
+    pub fn with_force_law(self, force_law: ForceLaw) -> BasicUniverse {
+        BasicUniverse { force_law, ..self }
+    }
 }
 
 impl Universe for BasicUniverse {
     fn advance(&mut self, time: f64) {
-        for a in &mut self.acceleration_buffer {
-            *a = Vector2 { x: 0.0, y: 0.0 };
+        compute_pairwise_accelerations(
+            &self.bodies,
+            self.force_law,
+            self.softening,
+            &mut self.acceleration_buffer,
+        );
+
+        add_external_accelerations(
+            &self.external_fields,
+            self.time,
+            self.bodies.iter().map(|b| b.position),
+            &mut self.acceleration_buffer,
+        );
+
+        if let Some(speed_of_light) = self.speed_of_light {
+            add_post_newtonian_accelerations(
+                &self.bodies,
+                speed_of_light,
+                &mut self.post_newtonian_buffer,
+                &mut self.acceleration_buffer,
+            );
         }
 
-        for ((i, body_i), (j, body_j)) in self.bodies.iter().enumerate().tuple_combinations() {
-            let distance = body_j.position - body_i.position;
-            let magnitude = pow_negative_one_half(distance.magnitude2());
+        let power = add_drag_accelerations(
+            &self.drags,
+            self.bodies.iter().map(|b| (b.mass, b.velocity)),
+            &mut self.acceleration_buffer,
+        );
+
+        self.dissipated_energy += power * time;
+
+        if let Some(speed_of_light) = self.radiation_reaction {
+            let power =
+                add_radiation_reaction_accelerations(&self.bodies, speed_of_light, &mut self.acceleration_buffer);
+
+            self.radiated_energy += power * time;
+        }
 
-            self.acceleration_buffer[i] += distance * (body_j.mass * magnitude);
-            self.acceleration_buffer[j] -= distance * (body_i.mass * magnitude);
+        // Test particles are updated before the bodies move, so they feel the same positions as the bodies.
+
+        compute_test_particle_accelerations(
+            &self.bodies,
+            self.force_law,
+            self.softening,
+            &self.test_particles,
+            &mut self.test_particle_acceleration_buffer,
+        );
+
+        add_external_accelerations(
+            &self.external_fields,
+            self.time,
+            self.test_particles.iter().map(|p| p.position),
+            &mut self.test_particle_acceleration_buffer,
+        );
+
+        add_drag_accelerations(
+            &self.drags,
+            self.test_particles.iter().map(|p| (0.0, p.velocity)),
+            &mut self.test_particle_acceleration_buffer,
+        );
+
+        for (test_particle, acceleration) in self
+            .test_particles
+            .iter_mut()
+            .zip(&self.test_particle_acceleration_buffer)
+        {
+            test_particle.velocity += acceleration * time;
+            test_particle.position += test_particle.velocity * time;
         }
 
-        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
-            body.velocity += acceleration * time;
-            body.position += body.velocity * time;
+        self.removed_bodies.clear();
+
+        if let Collision::Bounce { restitution } = self.collision {
+            for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+                body.velocity += acceleration * time;
+            }
+
+            drift_with_bounces(&mut self.bodies, time, restitution);
+        } else {
+            for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+                body.velocity += acceleration * time;
+                body.position += body.velocity * time;
+            }
+
+            if self.collision == Collision::Merge {
+                merge_overlapping_bodies(&mut self.bodies, &mut self.removed_bodies);
+                self.acceleration_buffer.truncate(self.bodies.len());
+            }
         }
+
+        self.time += time;
     }
 
     fn get_bodies(&self) -> &[Body] {
         &self.bodies
     }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+
+    fn get_removed_bodies(&self) -> &[usize] {
+        &self.removed_bodies
+    }
+
+    fn get_test_particles(&self) -> &[TestParticle] {
+        &self.test_particles
+    }
+
+    fn get_external_fields(&self) -> &[ExternalField] {
+        &self.external_fields
+    }
+
+    fn get_radiated_energy(&self) -> f64 {
+        self.radiated_energy
+    }
+
+    fn get_dissipated_energy(&self) -> f64 {
+        self.dissipated_energy
+    }
+
+    fn get_time(&self) -> f64 {
+        self.time
+    }
+
+    fn get_force_law(&self) -> ForceLaw {
+        self.force_law
+    }
+
+    fn get_softening(&self) -> Softening {
+        self.softening
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{pow_negative_one_half, BasicUniverse};
+    use super::BasicUniverse;
     use crate::body::Body;
+    use crate::collision::Collision;
+    use crate::drag::Drag;
+    use crate::external_field::ExternalField;
+    use crate::force_law::ForceLaw;
+    use crate::precession::ApsidalPrecession;
+    use crate::softening::Softening;
+    use crate::test_particle::TestParticle;
+    use crate::test_utilities::{check_trajectory, get_binary_bodies, get_taylor_bodies};
     use crate::universe::Universe;
     use cgmath::{InnerSpace, Vector2};
-
-    #[test]
-    fn check_pow_negative_one_half() {
-        let mut i = 1.0e-6;
-
-        while i < 1.0e6 {
-            assert!((pow_negative_one_half(i) - i.powf(-1.5)).abs() < 1e-6);
-
-            i *= 1.0001;
-        }
-    }
+    use std::f64::consts::{PI, SQRT_2};
 
     fn get_taylor_universe() -> BasicUniverse {
         BasicUniverse::new(&[
@@ -151,4 +349,321 @@ mod tests {
 
         assert!((m1 - expected_angular_momentum).abs() < 1.0e-8);
     }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(get_taylor_universe(), 0.000001, 100000, 1.0e-16);
+    }
+
+    #[test]
+    fn softening() {
+        // A head-on collision, which diverges without softening.
+
+        for &softening in &[Softening::Plummer(0.05), Softening::Spline(0.15)] {
+            let mut bodies = get_binary_bodies(0.0);
+
+            for body in &mut bodies {
+                body.velocity *= 0.0;
+            }
+
+            let mut universe = BasicUniverse::new(&bodies).with_softening(softening);
+            let e0 = universe.get_energy();
+
+            for _ in 0..100000 {
+                universe.advance(0.00001);
+            }
+
+            assert!((universe.get_energy() - e0).abs() < 1.0e-3 * e0.abs());
+        }
+    }
+
+    #[test]
+    fn force_laws() {
+        for &force_law in &[
+            ForceLaw::InversePower(3.0),
+            ForceLaw::Yukawa(1.0),
+            ForceLaw::LennardJones {
+                depth: 1.0,
+                length: 0.5,
+            },
+            ForceLaw::Harmonic(1.0),
+        ] {
+            let mut universe = BasicUniverse::new(&get_binary_bodies(0.5)).with_force_law(force_law);
+
+            let e0 = universe.get_energy();
+            let momentum = universe.get_momentum();
+
+            for _ in 0..100000 {
+                universe.advance(0.00001);
+            }
+
+            assert!((universe.get_energy() - e0).abs() < 1.0e-5 * e0.abs());
+            assert!((universe.get_momentum() - momentum).magnitude() < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn charges() {
+        // Like charges overcoming gravity push bodies at rest apart.
+
+        let bodies = get_binary_bodies(0.0)
+            .into_iter()
+            .map(|b| Body::new(b.mass, b.position, b.velocity * 0.0).with_charge(2.0))
+            .collect::<Vec<_>>();
+
+        let mut universe = BasicUniverse::new(&bodies);
+        let e0 = universe.get_energy();
+
+        for _ in 0..100000 {
+            universe.advance(0.00001);
+        }
+
+        assert!((universe.get_energy() - e0).abs() < 1.0e-5 * e0.abs());
+        assert!((universe.get_bodies()[1].position - universe.get_bodies()[0].position).magnitude() > 2.0);
+
+        // Like charges in a harmonic trap rest where the repulsion balances the trap.
+
+        let bodies = get_binary_bodies(0.0)
+            .into_iter()
+            .map(|b| Body::new(b.mass, b.position, b.velocity * 0.0).with_charge(1.0))
+            .collect::<Vec<_>>();
+
+        let mut universe = BasicUniverse::new(&bodies).with_force_law(ForceLaw::Harmonic(1.0));
+
+        for _ in 0..1000 {
+            universe.advance(0.001);
+        }
+
+        for (body, initial_body) in universe.get_bodies().iter().zip(&bodies) {
+            assert!((body.position - initial_body.position).magnitude() < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn massless_charges() {
+        // A charged massless body neither pushes charged bodies nor gets pushed by them, so momentum is conserved.
+
+        let mut bodies = get_binary_bodies(0.5)
+            .into_iter()
+            .map(|b| b.with_charge(0.5))
+            .collect::<Vec<_>>();
+
+        bodies.push(Body::new(0.0, Vector2::new(0.0, 0.5), Vector2::new(0.0, 0.0)).with_charge(1.0));
+
+        let mut universe = BasicUniverse::new(&bodies);
+        let momentum = universe.get_momentum();
+
+        for _ in 0..10000 {
+            universe.advance(0.0001);
+        }
+
+        assert!((universe.get_momentum() - momentum).magnitude() < 1.0e-12);
+    }
+
+    #[test]
+    fn test_particles() {
+        // Test particles move like bodies of negligible mass, without disturbing the bodies.
+
+        let bodies = get_taylor_bodies();
+
+        let test_particles = bodies
+            .iter()
+            .map(|b| TestParticle::new(b.position + Vector2::new(1.0, 0.5), b.velocity))
+            .collect::<Vec<_>>();
+
+        let light_bodies = bodies
+            .iter()
+            .cloned()
+            .chain(
+                test_particles
+                    .iter()
+                    .map(|p| Body::new(1.0e-20, p.position, p.velocity)),
+            )
+            .collect::<Vec<_>>();
+
+        let mut universe = BasicUniverse::new(&bodies).with_test_particles(&test_particles);
+
+        let mut light_universe = BasicUniverse::new(&light_bodies);
+
+        for _ in 0..1000 {
+            universe.advance(0.0001);
+            light_universe.advance(0.0001);
+        }
+
+        let (light_bodies, light_test_particles) = light_universe.get_bodies().split_at(bodies.len());
+
+        for (body, light_body) in universe.get_bodies().iter().zip(light_bodies) {
+            assert!((body.position - light_body.position).magnitude() < 1.0e-12);
+        }
+
+        for (test_particle, light_test_particle) in universe.get_test_particles().iter().zip(light_test_particles) {
+            assert!((test_particle.position - light_test_particle.position).magnitude() < 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn external_fields() {
+        // Energy is conserved in static fields.
+
+        let external_fields = [
+            ExternalField::LogarithmicHalo {
+                velocity: 1.0,
+                core_radius: 0.5,
+            },
+            ExternalField::MiyamotoNagai {
+                mass: 2.0,
+                length: 0.3,
+                height: 0.1,
+            },
+            ExternalField::Uniform(Vector2::new(0.5, 0.0)),
+        ];
+
+        let mut universe = BasicUniverse::new(&get_binary_bodies(0.5)).with_external_fields(&external_fields);
+
+        let e0 = universe.get_energy();
+
+        for _ in 0..100000 {
+            universe.advance(0.00001);
+        }
+
+        assert!((universe.get_energy() - e0).abs() < 1.0e-5 * e0.abs());
+    }
+
+    #[test]
+    fn post_newtonian() {
+        // The periapsis of a binary advances by 6π M / (c² a (1 - e²)) per orbit, where M is the total mass, and a and
+        // e are the semi-major axis and the eccentricity of the relative orbit.
+
+        let eccentricity = 0.5;
+        let speed_of_light = 100.0;
+        let period = PI * SQRT_2;
+        let expected_rate =
+            6.0 * PI * 2.0 / (speed_of_light * speed_of_light * (1.0 - eccentricity * eccentricity)) / period;
+
+        for &(speed_of_light, expected_rate) in &[(None, 0.0), (Some(speed_of_light), expected_rate)] {
+            let mut universe = BasicUniverse::new(&get_binary_bodies(eccentricity));
+
+            if let Some(speed_of_light) = speed_of_light {
+                universe = universe.with_speed_of_light(speed_of_light);
+            }
+
+            let mut precession = ApsidalPrecession::new(0, 1);
+
+            for _ in 0..2000000 {
+                universe.advance(0.00001);
+                precession.record(universe.get_time(), universe.get_bodies());
+            }
+
+            assert!(precession.get_periapses().len() >= 4);
+            assert!(
+                (precession.get_precession_rate().unwrap() - expected_rate).abs() < 1.0e-8 + 1.0e-3 * expected_rate
+            );
+        }
+    }
+
+    #[test]
+    fn radiation_reaction() {
+        // A circular binary shrinks as a⁴ = a₀⁴ - 4 β t by Peters' formula, where β = 64/5 m₁ m₂ M / c⁵, and the
+        // radiated energy makes up for the lost energy.
+
+        let speed_of_light: f64 = 5.0;
+        let beta = 64.0 / 5.0 * 2.0 / speed_of_light.powi(5);
+
+        let mut universe = BasicUniverse::new(&get_binary_bodies(0.0)).with_radiation_reaction(speed_of_light);
+
+        let e0 = universe.get_energy();
+
+        for _ in 0..1000000 {
+            universe.advance(0.00001);
+        }
+
+        let separation = (universe.get_bodies()[1].position - universe.get_bodies()[0].position).magnitude();
+        let expected_separation = (1.0 - 4.0 * beta * universe.get_time()).powf(0.25);
+        assert!((separation - expected_separation).abs() < 5.0e-3);
+        assert!(
+            (universe.get_energy() + universe.get_radiated_energy() - e0).abs()
+                < 1.0e-5 * universe.get_radiated_energy()
+        );
+    }
+
+    #[test]
+    fn drags() {
+        // A lone body slows down exponentially with linear drag.
+
+        let bodies = [Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(3.0, 4.0))];
+        let mut universe = BasicUniverse::new(&bodies).with_drags(&[Drag::Linear(2.0)]);
+
+        for _ in 0..100000 {
+            universe.advance(0.00001);
+        }
+
+        assert!((universe.get_bodies()[0].velocity.magnitude() - 5.0 * (-2.0f64).exp()).abs() < 1.0e-4);
+
+        // The dissipated energy makes up for the lost energy.
+
+        for &drag in &[
+            Drag::Linear(0.5),
+            Drag::Quadratic(0.5),
+            Drag::DynamicalFriction {
+                density: 0.01,
+                velocity_dispersion: 0.3,
+                coulomb_logarithm: 3.0,
+            },
+        ] {
+            let mut universe = BasicUniverse::new(&get_binary_bodies(0.5)).with_drags(&[drag]);
+
+            let e0 = universe.get_energy();
+
+            for _ in 0..100000 {
+                universe.advance(0.00001);
+            }
+
+            let dissipated_energy = universe.get_dissipated_energy();
+
+            assert!(dissipated_energy > 0.0);
+            assert!((universe.get_energy() + dissipated_energy - e0).abs() < 1.0e-4 * dissipated_energy);
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let bodies = get_binary_bodies(0.0)
+            .into_iter()
+            .map(|b| Body::new(b.mass, b.position, b.velocity * 0.0).with_radius(0.1))
+            .collect::<Vec<_>>();
+
+        let mut universe = BasicUniverse::new(&bodies).with_collision(Collision::Merge);
+        let momentum = universe.get_momentum();
+        let mut removed_bodies = Vec::new();
+
+        while universe.get_bodies().len() > 1 {
+            universe.advance(0.0001);
+            removed_bodies.extend_from_slice(universe.get_removed_bodies());
+        }
+
+        assert_eq!(removed_bodies, [1]);
+        assert_eq!(universe.get_mass(), 2.0);
+        assert_eq!(universe.get_momentum(), momentum);
+        assert!(universe.get_bodies()[0].position.magnitude() < 1.0e-12);
+    }
+
+    #[test]
+    fn bounce() {
+        let bodies = get_binary_bodies(0.0)
+            .into_iter()
+            .map(|b| Body::new(b.mass, b.position, b.velocity * 0.0).with_radius(0.1))
+            .collect::<Vec<_>>();
+
+        let mut universe = BasicUniverse::new(&bodies).with_collision(Collision::Bounce { restitution: 1.0 });
+        let momentum = universe.get_momentum();
+
+        for _ in 0..10000 {
+            universe.advance(0.0001);
+
+            assert!((universe.get_momentum() - momentum).magnitude() < 1.0e-12);
+            assert!(
+                (universe.get_bodies()[1].position - universe.get_bodies()[0].position).magnitude() >= 0.2 - 1.0e-12
+            );
+        }
+    }
 }
@@ -1,8 +1,11 @@
 use cgmath::Vector2;
+use std::f64::consts::PI;
 
 #[derive(Clone)]
 pub struct Body {
     pub mass: f64,
+    pub radius: f64, // Only used for collisions, where 0 means a point mass.
+    pub charge: f64, // Electric charge in units where the Coulomb constant is 1, like the gravitational constant.
     pub position: Vector2<f64>,
     pub velocity: Vector2<f64>,
 }
@@ -11,8 +14,27 @@ impl Body {
     pub fn new(mass: f64, position: Vector2<f64>, velocity: Vector2<f64>) -> Body {
         Body {
             mass,
+            radius: 0.0,
+            charge: 0.0,
             position,
             velocity,
         }
     }
+
+    pub fn with_radius(self, radius: f64) -> Body {
+        Body { radius, ..self }
+    }
+
+    /// Sets the charge, which universes with Coulomb forces take into account, so like charges repel and unlike charges
+    /// attract in addition to gravity. Massless bodies neither feel nor exert Coulomb forces.
+    pub fn with_charge(self, charge: f64) -> Body {
+        Body { charge, ..self }
+    }
+
+    /// Sets the radius to that of a uniform ball with the given density.
+    pub fn with_density(self, density: f64) -> Body {
+        let radius = (self.mass * 0.75 / (PI * density)).cbrt();
+
+        self.with_radius(radius)
+    }
 }
@@ -0,0 +1,27 @@
+use crate::body::Body;
+use cgmath::Vector3;
+
+/// Body in 3D space.
+#[derive(Clone)]
+pub struct Body3 {
+    pub mass: f64,
+    pub position: Vector3<f64>,
+    pub velocity: Vector3<f64>,
+}
+
+impl Body3 {
+    pub fn new(mass: f64, position: Vector3<f64>, velocity: Vector3<f64>) -> Body3 {
+        Body3 {
+            mass,
+            position,
+            velocity,
+        }
+    }
+}
+
+impl From<&Body> for Body3 {
+    /// Places the body in the plane z = 0.
+    fn from(body: &Body) -> Body3 {
+        Body3::new(body.mass, body.position.extend(0.0), body.velocity.extend(0.0))
+    }
+}
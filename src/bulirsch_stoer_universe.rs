@@ -0,0 +1,290 @@
+use crate::body::Body;
+use crate::forces::compute_accelerations;
+use crate::universe::Universe;
+use cgmath::{InnerSpace, Vector2};
+
+// Number of midpoint substeps for each extrapolation level, see <https://doi.org/10.1007/BF01386092>.
+const SUBSTEPS: [usize; 8] = [2, 4, 6, 8, 10, 12, 14, 16];
+
+// Extrapolation level that the step size control aims for.
+const TARGET_LEVEL: usize = 5;
+
+/// Gragg-Bulirsch-Stoer universe, which extrapolates modified midpoint integrations with decreasing substep sizes
+/// to zero substep size. Suitable for computing reference trajectories close to machine precision.
+pub struct BulirschStoerUniverse {
+    bodies: Vec<Body>,
+    absolute_tolerance: f64,
+    relative_tolerance: f64,
+    step_size: f64,                           // State.
+    steps: u64,                               // Statistics.
+    rejected_steps: u64,                      // Statistics.
+    state: Vec<Vector2<f64>>,                 // Positions followed by velocities.
+    tableau: Vec<Vec<Vec<Vector2<f64>>>>,     // Buffer.
+    midpoint_buffers: [Vec<Vector2<f64>>; 3], // Buffer.
+    scratch_bodies: Vec<Body>,                // Buffer.
+}
+
+fn compute_derivatives(scratch_bodies: &mut [Body], state: &[Vector2<f64>], derivatives: &mut [Vector2<f64>]) {
+    let (positions, velocities) = state.split_at(scratch_bodies.len());
+    let (position_derivatives, velocity_derivatives) = derivatives.split_at_mut(scratch_bodies.len());
+
+    for (body, position) in scratch_bodies.iter_mut().zip(positions) {
+        body.position = *position;
+    }
+
+    position_derivatives.copy_from_slice(velocities);
+    compute_accelerations(scratch_bodies, velocity_derivatives);
+}
+
+impl BulirschStoerUniverse {
+    pub fn new(bodies: &[Body], absolute_tolerance: f64, relative_tolerance: f64) -> BulirschStoerUniverse {
+        let state = bodies
+            .iter()
+            .map(|b| b.position)
+            .chain(bodies.iter().map(|b| b.velocity))
+            .collect::<Vec<_>>();
+
+        BulirschStoerUniverse {
+            bodies: bodies.to_vec(),
+            absolute_tolerance,
+            relative_tolerance,
+            step_size: f64::INFINITY,
+            steps: 0,
+            rejected_steps: 0,
+            tableau: (0..SUBSTEPS.len())
+                .map(|level| vec![state.clone(); level + 1])
+                .collect(),
+            midpoint_buffers: [state.clone(), state.clone(), state.clone()],
+            state,
+            scratch_bodies: bodies.to_vec(),
+        }
+    }
+
+    /// Number of accepted steps taken so far.
+    pub fn get_steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Number of steps discarded for not converging so far.
+    pub fn get_rejected_steps(&self) -> u64 {
+        self.rejected_steps
+    }
+
+    // Integrates the state with the modified midpoint method and stores the result at the start of the given tableau
+    // row.
+    fn modified_midpoint(&mut self, step_size: f64, substeps: usize, row: usize) {
+        let h = step_size / substeps as f64;
+        let [previous, current, derivatives] = &mut self.midpoint_buffers;
+
+        previous.copy_from_slice(&self.state);
+        compute_derivatives(&mut self.scratch_bodies, previous, derivatives);
+
+        for ((c, p), d) in current.iter_mut().zip(previous.iter()).zip(derivatives.iter()) {
+            *c = p + d * h;
+        }
+
+        for _ in 1..substeps {
+            compute_derivatives(&mut self.scratch_bodies, current, derivatives);
+
+            for ((c, p), d) in current.iter_mut().zip(previous.iter_mut()).zip(derivatives.iter()) {
+                let next = *p + d * (2.0 * h);
+
+                *p = *c;
+                *c = next;
+            }
+        }
+
+        compute_derivatives(&mut self.scratch_bodies, current, derivatives);
+
+        for (((r, c), p), d) in self.tableau[row][0]
+            .iter_mut()
+            .zip(current.iter())
+            .zip(previous.iter())
+            .zip(derivatives.iter())
+        {
+            *r = (c + p + d * h) * 0.5;
+        }
+    }
+
+    // Computes the normalized error between the two highest order estimations of the given level.
+    fn compute_error(&self, level: usize) -> f64 {
+        let row = &self.tableau[level];
+
+        let error = row[level]
+            .iter()
+            .zip(&row[level - 1])
+            .zip(&self.state)
+            .map(|((new, old), initial)| {
+                let scale =
+                    self.absolute_tolerance + self.relative_tolerance * new.magnitude().max(initial.magnitude());
+
+                ((new - old) / scale).magnitude2()
+            })
+            .sum::<f64>();
+
+        (error / self.state.len() as f64).sqrt()
+    }
+
+    // Tries to take a step, and returns the extrapolation level used if the step converged.
+    fn try_step(&mut self, step_size: f64) -> Option<usize> {
+        for (level, &substeps) in SUBSTEPS.iter().enumerate() {
+            self.modified_midpoint(step_size, substeps, level);
+
+            // Neville extrapolation to zero substep size.
+
+            let (previous_rows, current_rows) = self.tableau.split_at_mut(level);
+            let current_row = &mut current_rows[0];
+
+            for order in 1..=level {
+                let ratio = substeps as f64 / SUBSTEPS[level - order] as f64;
+                let factor = (ratio * ratio - 1.0).recip();
+                let (lower_orders, higher_orders) = current_row.split_at_mut(order);
+
+                for ((value, lower), previous) in higher_orders[0]
+                    .iter_mut()
+                    .zip(&lower_orders[order - 1])
+                    .zip(&previous_rows[level - 1][order - 1])
+                {
+                    *value = lower + (lower - previous) * factor;
+                }
+            }
+
+            if level > 0 {
+                let error = self.compute_error(level);
+
+                // No step size can help once the derivatives are not finite, as with coincident bodies.
+
+                assert!(error.is_finite(), "Bulirsch-Stoer error estimation is not finite");
+
+                if error <= 1.0 {
+                    return Some(level);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Universe for BulirschStoerUniverse {
+    fn advance(&mut self, time: f64) {
+        if self.bodies.is_empty() {
+            return;
+        }
+
+        let direction = time.signum();
+        let mut remaining_time = time.abs();
+
+        while remaining_time > 0.0 {
+            let step_size = self.step_size.min(remaining_time);
+
+            if let Some(level) = self.try_step(step_size * direction) {
+                self.state.copy_from_slice(&self.tableau[level][level]);
+
+                remaining_time -= step_size;
+                self.steps += 1;
+
+                let next_step_size = if level < TARGET_LEVEL {
+                    step_size * 1.5
+                } else if level > TARGET_LEVEL {
+                    step_size * 0.7
+                } else {
+                    step_size
+                };
+
+                if step_size < self.step_size {
+                    self.step_size = self.step_size.max(next_step_size);
+                } else {
+                    self.step_size = next_step_size;
+                }
+            } else {
+                self.rejected_steps += 1;
+                self.step_size = step_size * 0.5;
+
+                assert!(self.step_size > 0.0, "Bulirsch-Stoer step size underflowed");
+            }
+        }
+
+        let (positions, velocities) = self.state.split_at(self.bodies.len());
+
+        for ((body, position), velocity) in self.bodies.iter_mut().zip(positions).zip(velocities) {
+            body.position = *position;
+            body.velocity = *velocity;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BulirschStoerUniverse;
+    use crate::body::Body;
+    use crate::test_utilities::{
+        check_angular_momentum, check_binary_orbit, check_center_of_mass, check_energy, check_momentum,
+        check_reversibility, get_binary_bodies, get_taylor_bodies,
+    };
+    use crate::universe::Universe;
+    use cgmath::Vector2;
+
+    fn get_taylor_universe() -> BulirschStoerUniverse {
+        BulirschStoerUniverse::new(&get_taylor_bodies(), 1.0e-14, 1.0e-14)
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(get_taylor_universe(), 0.1, 10, 1.0e-9);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        check_center_of_mass(get_taylor_universe(), 0.1, 10, 1.0e-23);
+    }
+
+    #[test]
+    fn momentum() {
+        check_momentum(get_taylor_universe(), 0.1, 10, 1.0e-18);
+    }
+
+    #[test]
+    fn angular_momentun() {
+        check_angular_momentum(get_taylor_universe(), 0.1, 10, 1.0e-8);
+    }
+
+    #[test]
+    fn reversibility() {
+        // Not time-symmetric, but accurate enough to return along the same trajectory.
+
+        check_reversibility(get_taylor_universe(), 0.1, 10, 1.0e-20);
+    }
+
+    #[test]
+    fn close_encounter() {
+        let mut universe = BulirschStoerUniverse::new(&get_binary_bodies(0.999), 1.0e-14, 1.0e-14);
+
+        check_binary_orbit(&mut universe, 100, 1.0e-10, 1.0e-20);
+    }
+
+    #[test]
+    fn empty() {
+        let mut universe = BulirschStoerUniverse::new(&[], 1.0e-14, 1.0e-14);
+
+        universe.advance(0.1);
+
+        assert_eq!(universe.get_steps(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not finite")]
+    fn coincident_bodies() {
+        let body = Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0));
+
+        BulirschStoerUniverse::new(&[body.clone(), body], 1.0e-14, 1.0e-14).advance(0.1);
+    }
+}
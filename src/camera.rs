@@ -0,0 +1,117 @@
+use crate::projection::Projection;
+use cgmath::{InnerSpace, Vector2, Vector3};
+
+/// Perspective camera, which projects points in 3D space onto the canvas.
+#[derive(Clone)]
+pub struct Camera {
+    position: Vector3<f64>,
+    right: Vector3<f64>,   // Pre-calculated.
+    up: Vector3<f64>,      // Pre-calculated.
+    forward: Vector3<f64>, // Pre-calculated.
+    focal_length: f64,
+    near_distance: f64,
+}
+
+impl Camera {
+    /// The camera at `position` looks at `target`, with `up` pointing upwards on the canvas. Points at distance
+    /// `focal_length` in front of the camera keep their scale, and points closer than `near_distance` are projected as
+    /// if they were at that distance.
+    pub fn new(
+        position: Vector3<f64>,
+        target: Vector3<f64>,
+        up: Vector3<f64>,
+        focal_length: f64,
+        near_distance: f64,
+    ) -> Camera {
+        let forward = (target - position).normalize();
+        let right = forward.cross(up).normalize();
+
+        Camera {
+            position,
+            right,
+            up: right.cross(forward),
+            forward,
+            focal_length,
+            near_distance,
+        }
+    }
+
+    /// Camera on the negative z axis looking at the origin, which projects the plane z = 0 like 2D space is drawn.
+    pub fn front(distance: f64) -> Camera {
+        Camera::new(
+            Vector3::new(0.0, 0.0, -distance),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            distance,
+            distance * 1.0e-3,
+        )
+    }
+}
+
+impl Projection for Camera {
+    fn project(&self, point: Vector3<f64>) -> Vector2<f64> {
+        let offset = point - self.position;
+        let scale = self.focal_length / offset.dot(self.forward).max(self.near_distance);
+
+        Vector2::new(offset.dot(self.right), -offset.dot(self.up)) * scale
+    }
+
+    fn project_velocity(&self, point: Vector3<f64>, velocity: Vector3<f64>) -> Vector2<f64> {
+        let offset = point - self.position;
+        let depth = offset.dot(self.forward);
+
+        let depth_velocity = if depth > self.near_distance {
+            velocity.dot(self.forward)
+        } else {
+            0.0
+        };
+
+        let depth = depth.max(self.near_distance);
+        let canvas_offset = Vector2::new(offset.dot(self.right), -offset.dot(self.up));
+        let canvas_velocity = Vector2::new(velocity.dot(self.right), -velocity.dot(self.up));
+
+        (canvas_velocity * depth - canvas_offset * depth_velocity) * (self.focal_length / (depth * depth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Camera;
+    use crate::projection::Projection;
+    use cgmath::{InnerSpace, Vector2, Vector3};
+
+    #[test]
+    fn front() {
+        let camera = Camera::front(1000.0);
+
+        assert!((camera.project(Vector3::new(3.0, -5.0, 0.0)) - Vector2::new(3.0, -5.0)).magnitude() < 1.0e-12);
+        assert!((camera.project(Vector3::new(3.0, -5.0, 1000.0)) - Vector2::new(1.5, -2.5)).magnitude() < 1.0e-12);
+    }
+
+    #[test]
+    fn perspective() {
+        let camera = Camera::new(
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            2.0,
+            0.1,
+        );
+
+        // Looking along negative x, the right side is positive y.
+
+        assert!((camera.project(Vector3::new(0.0, 5.0, 0.0)) - Vector2::new(1.0, 0.0)).magnitude() < 1.0e-12);
+        assert!((camera.project(Vector3::new(5.0, 0.0, 5.0)) - Vector2::new(0.0, -2.0)).magnitude() < 1.0e-12);
+        assert!((camera.project(Vector3::new(20.0, 1.0, 0.0)) - Vector2::new(20.0, 0.0)).magnitude() < 1.0e-12);
+
+        // Projected velocities are derivatives of projected positions.
+
+        let point = Vector3::new(1.0, 2.0, 3.0);
+        let velocity = Vector3::new(-4.0, 5.0, 6.0);
+        let time = 1.0e-6;
+        let expected =
+            (camera.project(point + velocity * time) - camera.project(point - velocity * time)) / (2.0 * time);
+
+        assert!((camera.project_velocity(point, velocity) - expected).magnitude() < 1.0e-6);
+    }
+}
@@ -0,0 +1,314 @@
+use crate::body::Body;
+use cgmath::InnerSpace;
+
+// Bounds the work of a single drift where bodies are in lasting contact.
+const MAX_IMPACTS: usize = 1000;
+
+/// How bodies with overlapping radii interact.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Collision {
+    /// Bodies pass through each other.
+    #[default]
+    None,
+    /// Overlapping bodies merge into one, conserving mass, charge, momentum, center of mass and volume.
+    Merge,
+    /// Bodies bounce off each other, where the restitution is the ratio of the separating speed to the approaching
+    /// speed, so 1 is elastic and 0 is perfectly inelastic. Impacts are found between steps, so fast bodies do not pass
+    /// through each other.
+    Bounce { restitution: f64 },
+}
+
+// Merges the two bodies into the first one.
+fn merge(target: &mut Body, source: &Body) {
+    let mass = target.mass + source.mass;
+
+    target.position = (target.position * target.mass + source.position * source.mass) / mass;
+    target.velocity = (target.velocity * target.mass + source.velocity * source.mass) / mass;
+    target.radius = (target.radius.powi(3) + source.radius.powi(3)).cbrt();
+    target.charge += source.charge;
+    target.mass = mass;
+}
+
+fn find_overlapping_pair(bodies: &[Body]) -> Option<(usize, usize)> {
+    for (i, body_i) in bodies.iter().enumerate() {
+        for (j, body_j) in bodies.iter().enumerate().skip(i + 1) {
+            let radius = body_i.radius + body_j.radius;
+
+            if (body_j.position - body_i.position).magnitude2() < radius * radius {
+                return Some((i, j));
+            }
+        }
+    }
+
+    None
+}
+
+/// Merges overlapping bodies until none overlap, where the body with the lower index absorbs the other one. The indices
+/// of the absorbed bodies, relative to the bodies before merging, are appended to `removed_bodies` in ascending order.
+pub fn merge_overlapping_bodies(bodies: &mut Vec<Body>, removed_bodies: &mut Vec<usize>) {
+    let first_removed = removed_bodies.len();
+
+    while let Some((i, j)) = find_overlapping_pair(bodies) {
+        let source = bodies.remove(j);
+
+        merge(&mut bodies[i], &source);
+
+        // Convert the index to the numbering before any removal.
+
+        let original_index =
+            removed_bodies[first_removed..]
+                .iter()
+                .fold(j, |index, &removed| if removed <= index { index + 1 } else { index });
+
+        let position = removed_bodies[first_removed..]
+            .iter()
+            .position(|&removed| removed > original_index)
+            .map_or(removed_bodies.len(), |position| first_removed + position);
+
+        removed_bodies.insert(position, original_index);
+    }
+}
+
+// Computes the time until the bodies touch while moving with their velocities, if they are approaching each other.
+fn compute_time_of_impact(body_i: &Body, body_j: &Body) -> Option<f64> {
+    let distance = body_j.position - body_i.position;
+    let velocity = body_j.velocity - body_i.velocity;
+    let radius = body_i.radius + body_j.radius;
+    let approaching_speed = -distance.dot(velocity);
+
+    if approaching_speed <= 0.0 {
+        return None;
+    }
+
+    // Smaller root of |distance + velocity t|² = radius², which is 0 for overlapping bodies.
+
+    let c = distance.magnitude2() - radius * radius;
+
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+
+    let discriminant = approaching_speed * approaching_speed - velocity.magnitude2() * c;
+
+    if discriminant < 0.0 {
+        None
+    } else {
+        Some(c / (approaching_speed + discriminant.sqrt()))
+    }
+}
+
+// Finds the first impact within the given time, apart from the given pair, which has just bounced. It is separating or
+// at rest up to rounding errors, which could otherwise make it bounce again and again without moving.
+fn find_first_impact(bodies: &[Body], time: f64, skipped_pair: Option<(usize, usize)>) -> Option<(usize, usize, f64)> {
+    let mut result = None;
+    let mut first_time = time;
+
+    for (i, body_i) in bodies.iter().enumerate() {
+        for (j, body_j) in bodies.iter().enumerate().skip(i + 1) {
+            if skipped_pair == Some((i, j)) {
+                continue;
+            }
+
+            if let Some(t) = compute_time_of_impact(body_i, body_j) {
+                if t <= first_time {
+                    result = Some((i, j, t));
+                    first_time = t;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+// Applies opposite impulses along the line between the centers, so the momentum is unchanged. Bodies with coincident
+// centers have no such line, and bodies that are not approaching each other are left alone.
+fn bounce(bodies: &mut [Body], i: usize, j: usize, restitution: f64) {
+    let (left, right) = bodies.split_at_mut(j);
+    let (body_i, body_j) = (&mut left[i], &mut right[0]);
+    let distance = body_j.position - body_i.position;
+    let distance_squared = distance.magnitude2();
+
+    if distance_squared == 0.0 {
+        return;
+    }
+
+    let normal = distance / distance_squared.sqrt();
+    let normal_speed = (body_j.velocity - body_i.velocity).dot(normal);
+
+    if normal_speed >= 0.0 {
+        return;
+    }
+
+    let impulse = -(1.0 + restitution) * normal_speed / (body_i.mass.recip() + body_j.mass.recip());
+
+    body_i.velocity -= normal * (impulse / body_i.mass);
+    body_j.velocity += normal * (impulse / body_j.mass);
+}
+
+/// Moves the bodies with their velocities for the given time, and bounces them off each other at the moments they
+/// touch. Backward in time, elastic bounces are retraced, while inelastic bounces still lose energy.
+pub fn drift_with_bounces(bodies: &mut [Body], time: f64, restitution: f64) {
+    // Backward in time, the bodies move forward with reversed velocities, where impacts are found the same way.
+
+    if time < 0.0 {
+        for body in bodies.iter_mut() {
+            body.velocity = -body.velocity;
+        }
+
+        drift_with_bounces(bodies, -time, restitution);
+
+        for body in bodies.iter_mut() {
+            body.velocity = -body.velocity;
+        }
+
+        return;
+    }
+
+    let mut remaining_time = time;
+    let mut last_pair = None;
+
+    for _ in 0..MAX_IMPACTS {
+        match find_first_impact(bodies, remaining_time, last_pair) {
+            Some((i, j, t)) => {
+                for body in bodies.iter_mut() {
+                    body.position += body.velocity * t;
+                }
+
+                bounce(bodies, i, j, restitution);
+
+                remaining_time -= t;
+                last_pair = Some((i, j));
+            }
+            None => break,
+        }
+    }
+
+    for body in bodies.iter_mut() {
+        body.position += body.velocity * remaining_time;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{drift_with_bounces, merge_overlapping_bodies};
+    use crate::body::Body;
+    use cgmath::{InnerSpace, Vector2};
+
+    #[test]
+    fn merge() {
+        let mut bodies = vec![
+            Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)).with_radius(1.0),
+            Body::new(2.0, Vector2::new(10.0, 0.0), Vector2::new(0.0, 0.0)).with_radius(1.0),
+            Body::new(3.0, Vector2::new(1.5, 0.0), Vector2::new(0.0, 1.0)).with_radius(1.0),
+            Body::new(4.0, Vector2::new(3.0, 0.0), Vector2::new(0.0, 0.0)).with_radius(1.0),
+            Body::new(5.0, Vector2::new(2.5, 0.0), Vector2::new(0.0, 0.0)),
+        ];
+
+        let mut removed_bodies = Vec::new();
+
+        merge_overlapping_bodies(&mut bodies, &mut removed_bodies);
+
+        // Body 2 is absorbed first, then the grown body reaches body 3, and the point mass at 2.5 is inside it too.
+
+        assert_eq!(removed_bodies, [2, 3, 4]);
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[0].mass, 13.0);
+        assert!((bodies[0].position - Vector2::new(29.0 / 13.0, 0.0)).magnitude() < 1.0e-12);
+        assert!((bodies[0].velocity - Vector2::new(1.0 / 13.0, 3.0 / 13.0)).magnitude() < 1.0e-12);
+        assert!((bodies[0].radius - 3.0f64.cbrt()).abs() < 1.0e-12);
+        assert_eq!(bodies[1].mass, 2.0);
+    }
+
+    #[test]
+    fn bounce() {
+        // Without continuous detection, the first body would pass through the second one within the step.
+
+        let mut bodies = vec![
+            Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(1000.0, 0.0)).with_radius(1.0),
+            Body::new(1.0, Vector2::new(10.0, 0.0), Vector2::new(0.0, 0.0)).with_radius(1.0),
+        ];
+
+        drift_with_bounces(&mut bodies, 1.0, 1.0);
+
+        assert!((bodies[0].position - Vector2::new(8.0, 0.0)).magnitude() < 1.0e-9);
+        assert!((bodies[1].position - Vector2::new(1002.0, 0.0)).magnitude() < 1.0e-9);
+        assert!((bodies[0].velocity - Vector2::new(0.0, 0.0)).magnitude() < 1.0e-12);
+        assert!((bodies[1].velocity - Vector2::new(1000.0, 0.0)).magnitude() < 1.0e-12);
+    }
+
+    #[test]
+    fn bounce_backward() {
+        // Going back in time, the first body bounces back off the second one to where it came from.
+
+        let bodies = vec![
+            Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(1000.0, 0.0)).with_radius(1.0),
+            Body::new(1.0, Vector2::new(10.0, 0.0), Vector2::new(0.0, 0.0)).with_radius(1.0),
+        ];
+
+        let mut new_bodies = bodies.clone();
+
+        drift_with_bounces(&mut new_bodies, 1.0, 1.0);
+        drift_with_bounces(&mut new_bodies, -1.0, 1.0);
+
+        for (body, new_body) in bodies.iter().zip(&new_bodies) {
+            assert!((body.position - new_body.position).magnitude() < 1.0e-9);
+            assert!((body.velocity - new_body.velocity).magnitude() < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn restitution() {
+        let mut bodies = vec![
+            Body::new(3.0, Vector2::new(0.0, 0.0), Vector2::new(2.0, 1.0)).with_radius(1.0),
+            Body::new(1.0, Vector2::new(4.0, 0.0), Vector2::new(-3.0, 1.0)).with_radius(0.5),
+        ];
+
+        let momentum = bodies[0].velocity * bodies[0].mass + bodies[1].velocity * bodies[1].mass;
+
+        drift_with_bounces(&mut bodies, 1.0, 0.5);
+
+        // The bodies separate at half the approaching speed of 5.
+
+        assert!((bodies[1].velocity - bodies[0].velocity - Vector2::new(2.5, 0.0)).magnitude() < 1.0e-12);
+
+        assert!(
+            (bodies[0].velocity * bodies[0].mass + bodies[1].velocity * bodies[1].mass - momentum).magnitude()
+                < 1.0e-14
+        );
+    }
+
+    #[test]
+    fn inelastic_contact() {
+        // The bodies move on together after the impact, without bouncing again while they touch.
+
+        let mut bodies = vec![
+            Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(2.0, 0.0)).with_radius(1.0),
+            Body::new(1.0, Vector2::new(3.0, 0.0), Vector2::new(0.0, 0.0)).with_radius(1.0),
+        ];
+
+        drift_with_bounces(&mut bodies, 2.0, 0.0);
+
+        assert!((bodies[0].position - Vector2::new(2.5, 0.0)).magnitude() < 1.0e-12);
+        assert!((bodies[1].position - Vector2::new(4.5, 0.0)).magnitude() < 1.0e-12);
+        assert!((bodies[0].velocity - Vector2::new(1.0, 0.0)).magnitude() < 1.0e-12);
+        assert!((bodies[1].velocity - Vector2::new(1.0, 0.0)).magnitude() < 1.0e-12);
+    }
+
+    #[test]
+    fn coincident_centers() {
+        // Point masses meeting head-on have no line between their centers, so they pass through each other.
+
+        let mut bodies = vec![
+            Body::new(1.0, Vector2::new(-1.0, 0.0), Vector2::new(1.0, 0.0)),
+            Body::new(1.0, Vector2::new(1.0, 0.0), Vector2::new(-1.0, 0.0)),
+        ];
+
+        drift_with_bounces(&mut bodies, 2.0, 1.0);
+
+        assert_eq!(bodies[0].position, Vector2::new(1.0, 0.0));
+        assert_eq!(bodies[1].position, Vector2::new(-1.0, 0.0));
+        assert_eq!(bodies[0].velocity, Vector2::new(1.0, 0.0));
+        assert_eq!(bodies[1].velocity, Vector2::new(-1.0, 0.0));
+    }
+}
@@ -0,0 +1,129 @@
+use cgmath::Vector2;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+    pub const ONE: Complex = Complex { re: 1.0, im: 0.0 };
+
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn conj(self) -> Complex {
+        Complex::new(self.re, -self.im)
+    }
+
+    pub fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    pub fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// The principal square root, with a nonnegative real part.
+    pub fn sqrt(self) -> Complex {
+        let norm = self.norm();
+
+        if norm == 0.0 {
+            Complex::ZERO
+        } else if self.re >= 0.0 {
+            let re = ((norm + self.re) * 0.5).sqrt();
+
+            Complex::new(re, self.im / (2.0 * re))
+        } else {
+            let im = ((norm - self.re) * 0.5).sqrt().copysign(self.im);
+
+            Complex::new(self.im / (2.0 * im), im)
+        }
+    }
+
+    pub fn recip(self) -> Complex {
+        let norm_sqr = self.norm_sqr();
+
+        Complex::new(self.re / norm_sqr, -self.im / norm_sqr)
+    }
+}
+
+impl From<Vector2<f64>> for Complex {
+    fn from(value: Vector2<f64>) -> Complex {
+        Complex::new(value.x, value.y)
+    }
+}
+
+impl From<Complex> for Vector2<f64> {
+    fn from(value: Complex) -> Vector2<f64> {
+        Vector2::new(value.re, value.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, rhs: Complex) -> Complex {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl AddAssign for Complex {
+    fn add_assign(&mut self, rhs: Complex) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, rhs: Complex) -> Complex {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl SubAssign for Complex {
+    fn sub_assign(&mut self, rhs: Complex) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: Complex) -> Complex {
+        Complex::new(self.re * rhs.re - self.im * rhs.im, self.re * rhs.im + self.im * rhs.re)
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: f64) -> Complex {
+        Complex::new(self.re * rhs, self.im * rhs)
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    fn div(self, rhs: Complex) -> Complex {
+        let norm_sqr = rhs.norm_sqr();
+
+        Complex::new(
+            (self.re * rhs.re + self.im * rhs.im) / norm_sqr,
+            (self.im * rhs.re - self.re * rhs.im) / norm_sqr,
+        )
+    }
+}
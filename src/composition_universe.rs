@@ -0,0 +1,100 @@
+use crate::body::Body;
+use crate::leapfrog_universe::LeapfrogUniverse;
+use crate::universe::Universe;
+
+// See <https://doi.org/10.1016/0167-2789(90)90019-L>.
+const FOREST_RUTH_COEFFICIENTS: [f64; 3] = [
+    1.351_207_191_959_657_8,
+    -1.702_414_383_919_315_5,
+    1.351_207_191_959_657_8,
+];
+
+// Solution A of <https://doi.org/10.1016/0375-9601(90)90092-3>.
+const YOSHIDA_COEFFICIENTS: [f64; 7] = [
+    0.784_513_610_477_560,
+    0.235_573_213_359_357,
+    -1.177_679_984_178_87,
+    1.315_186_320_683_906,
+    -1.177_679_984_178_87,
+    0.235_573_213_359_357,
+    0.784_513_610_477_560,
+];
+
+/// Symmetric composition of leapfrog steps, which raises the order of the integrator while keeping it symplectic.
+pub struct CompositionUniverse {
+    leapfrog: LeapfrogUniverse,
+    coefficients: &'static [f64],
+}
+
+impl CompositionUniverse {
+    /// Fourth order Forest-Ruth integrator.
+    pub fn forest_ruth(bodies: &[Body]) -> CompositionUniverse {
+        CompositionUniverse {
+            leapfrog: LeapfrogUniverse::new(bodies),
+            coefficients: &FOREST_RUTH_COEFFICIENTS,
+        }
+    }
+
+    /// Sixth order Yoshida integrator.
+    pub fn yoshida(bodies: &[Body]) -> CompositionUniverse {
+        CompositionUniverse {
+            leapfrog: LeapfrogUniverse::new(bodies),
+            coefficients: &YOSHIDA_COEFFICIENTS,
+        }
+    }
+}
+
+impl Universe for CompositionUniverse {
+    fn advance(&mut self, time: f64) {
+        for coefficient in self.coefficients {
+            self.leapfrog.advance(coefficient * time);
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        self.leapfrog.get_bodies()
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        self.leapfrog.has_coulomb_forces()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompositionUniverse, FOREST_RUTH_COEFFICIENTS, YOSHIDA_COEFFICIENTS};
+    use crate::test_utilities::{
+        check_angular_momentum, check_center_of_mass, check_energy, check_momentum, check_reversibility,
+        get_taylor_bodies,
+    };
+
+    #[test]
+    fn coefficients() {
+        for coefficients in &[&FOREST_RUTH_COEFFICIENTS[..], &YOSHIDA_COEFFICIENTS[..]] {
+            assert!((coefficients.iter().sum::<f64>() - 1.0).abs() < 1.0e-14);
+            assert!(coefficients.iter().eq(coefficients.iter().rev()));
+        }
+    }
+
+    #[test]
+    fn forest_ruth() {
+        let get_universe = || CompositionUniverse::forest_ruth(&get_taylor_bodies());
+
+        check_energy(get_universe(), 0.01, 100, 1.0e-6);
+        check_center_of_mass(get_universe(), 0.01, 100, 1.0e-23);
+        check_momentum(get_universe(), 0.01, 100, 1.0e-18);
+        check_angular_momentum(get_universe(), 0.01, 100, 1.0e-8);
+        check_reversibility(get_universe(), 0.01, 100, 1.0e-24);
+    }
+
+    #[test]
+    fn yoshida() {
+        let get_universe = || CompositionUniverse::yoshida(&get_taylor_bodies());
+
+        check_energy(get_universe(), 0.01, 100, 1.0e-8);
+        check_center_of_mass(get_universe(), 0.01, 100, 1.0e-23);
+        check_momentum(get_universe(), 0.01, 100, 1.0e-18);
+        check_angular_momentum(get_universe(), 0.01, 100, 1.0e-8);
+        check_reversibility(get_universe(), 0.01, 100, 1.0e-24);
+    }
+}
@@ -1,9 +1,11 @@
 use crate::body::Body;
 use crate::distributions::{Circle, Reciprocal};
+use crate::test_particle::TestParticle;
 use cgmath::Vector2;
 use rand::distributions::{Distribution, Uniform};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
+use std::f64::consts::PI;
 
 #[derive(Clone)]
 pub struct Color {
@@ -44,6 +46,7 @@ pub struct StyledBody {
 
 pub struct Configuration {
     pub bodies: Vec<StyledBody>,
+    pub test_particles: Vec<TestParticle>,
     pub sample_frequency: f64,
 }
 
@@ -60,7 +63,7 @@ fn normalize_bodies(mut bodies: Vec<StyledBody>) -> Vec<StyledBody> {
     bodies
 }
 
-pub fn random_configuration(seed: u64) -> Configuration {
+pub fn random_configuration(seed: u64, sample_frequency: f64) -> Configuration {
     let min_bodies = 2;
     let max_bodies = 5;
     let min_mass = 2.0f64.powf(4.0);
@@ -69,6 +72,7 @@ pub fn random_configuration(seed: u64) -> Configuration {
     let max_trail_width = 2.0f64.powf(1.0);
     let position_radius = 2.0f64.powf(8.0);
     let velocity_radius = 2.0f64.powf(5.0);
+    let density = 2.0f64.powf(13.0);
 
     let mut rng = StdRng::seed_from_u64(seed);
     let mass_rng = Reciprocal::new(min_mass, max_mass);
@@ -88,7 +92,8 @@ pub fn random_configuration(seed: u64) -> Configuration {
                 let trail_width = mass_to_trail_width(mass);
 
                 StyledBody {
-                    body: Body::new(mass, position_rng.sample(&mut rng), velocity_rng.sample(&mut rng)),
+                    body: Body::new(mass, position_rng.sample(&mut rng), velocity_rng.sample(&mut rng))
+                        .with_density(density),
                     color: random_color(&mut rng),
                     trail_width,
                 }
@@ -98,6 +103,113 @@ pub fn random_configuration(seed: u64) -> Configuration {
 
     Configuration {
         bodies,
-        sample_frequency: 1_000_000.0,
+        test_particles: Vec::new(),
+        sample_frequency,
+    }
+}
+
+pub fn galaxy_configuration(seed: u64, sample_frequency: f64) -> Configuration {
+    let star_count = 10_000;
+    let core_mass = 2.0f64.powf(20.0);
+    let star_mass = 1.0;
+    let min_radius = 2.0f64.powf(4.0);
+    let max_radius = 2.0f64.powf(8.0);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let radius_squared_rng = Uniform::new(min_radius * min_radius, max_radius * max_radius);
+    let angle_rng = Uniform::new(0.0, PI * 2.0);
+    let disk_mass = f64::from(star_count) * star_mass;
+
+    let core = StyledBody {
+        body: Body::new(core_mass, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0)),
+        color: random_color(&mut rng),
+        trail_width: 2.0,
+    };
+
+    // Stars are uniformly distributed in an annulus, moving on circular orbits around the mass inside their orbits.
+
+    let stars = (0..star_count).map(|_| {
+        let radius_squared = radius_squared_rng.sample(&mut rng);
+        let radius = radius_squared.sqrt();
+        let angle = angle_rng.sample(&mut rng);
+        let direction = Vector2::new(angle.cos(), angle.sin());
+
+        let inner_mass = core_mass
+            + disk_mass * (radius_squared - min_radius * min_radius)
+                / (max_radius * max_radius - min_radius * min_radius);
+
+        let speed = (inner_mass / radius).sqrt();
+
+        StyledBody {
+            body: Body::new(
+                star_mass,
+                direction * radius,
+                Vector2::new(-direction.y, direction.x) * speed,
+            ),
+            color: random_color(&mut rng),
+            trail_width: 2.0f64.powf(-3.0),
+        }
+    });
+
+    Configuration {
+        bodies: normalize_bodies(std::iter::once(core).chain(stars).collect()),
+        test_particles: Vec::new(),
+        sample_frequency,
+    }
+}
+
+pub fn ring_configuration(seed: u64, sample_frequency: f64) -> Configuration {
+    let test_particle_count = 2_000;
+    let planet_mass = 2.0f64.powf(20.0);
+    let moon_mass = 2.0f64.powf(12.0);
+    let moon_radius = 2.0f64.powf(7.5);
+    let min_radius = 2.0f64.powf(5.0);
+    let max_radius = 2.0f64.powf(8.0);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let radius_squared_rng = Uniform::new(min_radius * min_radius, max_radius * max_radius);
+    let angle_rng = Uniform::new(0.0, PI * 2.0);
+
+    // Circular orbit around the planet, ignoring the moon.
+    let orbit = |radius: f64, angle: f64| {
+        let direction = Vector2::new(angle.cos(), angle.sin());
+
+        (
+            direction * radius,
+            Vector2::new(-direction.y, direction.x) * (planet_mass / radius).sqrt(),
+        )
+    };
+
+    let (moon_position, moon_velocity) = orbit(moon_radius, angle_rng.sample(&mut rng));
+
+    let bodies = normalize_bodies(vec![
+        StyledBody {
+            body: Body::new(planet_mass, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0)),
+            color: random_color(&mut rng),
+            trail_width: 2.0,
+        },
+        StyledBody {
+            body: Body::new(moon_mass, moon_position, moon_velocity),
+            color: random_color(&mut rng),
+            trail_width: 1.0,
+        },
+    ]);
+
+    // The moon opens gaps in a ring of test particles, which are uniformly distributed in an annulus around the planet.
+
+    let planet = &bodies[0].body;
+
+    let test_particles = (0..test_particle_count)
+        .map(|_| {
+            let (position, velocity) = orbit(radius_squared_rng.sample(&mut rng).sqrt(), angle_rng.sample(&mut rng));
+
+            TestParticle::new(planet.position + position, planet.velocity + velocity)
+        })
+        .collect();
+
+    Configuration {
+        bodies,
+        test_particles,
+        sample_frequency,
     }
 }
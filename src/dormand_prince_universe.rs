@@ -0,0 +1,291 @@
+use crate::body::Body;
+use crate::forces::compute_accelerations;
+use crate::universe::Universe;
+use cgmath::{InnerSpace, Vector2};
+
+// Butcher tableau of the Dormand-Prince method, see <https://doi.org/10.1016/0771-050X(80)90013-3>.
+
+const A: [[f64; 6]; 7] = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+        0.0,
+        0.0,
+    ],
+    [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+        0.0,
+    ],
+    [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+
+// Difference between the fifth order and the embedded fourth order solutions.
+const E: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339_200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+
+const SAFETY_FACTOR: f64 = 0.9;
+const MIN_SCALE_FACTOR: f64 = 0.2;
+const MAX_SCALE_FACTOR: f64 = 5.0;
+
+/// Runge-Kutta universe that splits each `advance` call into substeps sized to keep the local error within the
+/// tolerances.
+pub struct DormandPrinceUniverse {
+    bodies: Vec<Body>,
+    absolute_tolerance: f64,
+    relative_tolerance: f64,
+    step_size: f64,                              // State.
+    substeps: u64,                               // Statistics.
+    rejected_substeps: u64,                      // Statistics.
+    stage_bodies: Vec<Body>,                     // Buffer.
+    velocity_stages: [Vec<Vector2<f64>>; 7],     // Buffer.
+    acceleration_stages: [Vec<Vector2<f64>>; 7], // Buffer.
+}
+
+impl DormandPrinceUniverse {
+    pub fn new(bodies: &[Body], absolute_tolerance: f64, relative_tolerance: f64) -> DormandPrinceUniverse {
+        let zeros = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+        let mut velocity_stages: [Vec<Vector2<f64>>; 7] = Default::default();
+        let mut acceleration_stages: [Vec<Vector2<f64>>; 7] = Default::default();
+
+        for (velocities, accelerations) in velocity_stages.iter_mut().zip(&mut acceleration_stages) {
+            *velocities = zeros.clone();
+            *accelerations = zeros.clone();
+        }
+
+        // The first stage always holds the derivatives of the current state.
+
+        velocity_stages[0] = bodies.iter().map(|b| b.velocity).collect();
+        compute_accelerations(bodies, &mut acceleration_stages[0]);
+
+        DormandPrinceUniverse {
+            bodies: bodies.to_vec(),
+            absolute_tolerance,
+            relative_tolerance,
+            step_size: f64::INFINITY,
+            substeps: 0,
+            rejected_substeps: 0,
+            stage_bodies: bodies.to_vec(),
+            velocity_stages,
+            acceleration_stages,
+        }
+    }
+
+    pub fn get_absolute_tolerance(&self) -> f64 {
+        self.absolute_tolerance
+    }
+
+    pub fn get_relative_tolerance(&self) -> f64 {
+        self.relative_tolerance
+    }
+
+    /// Number of accepted substeps taken so far.
+    pub fn get_substeps(&self) -> u64 {
+        self.substeps
+    }
+
+    /// Number of substeps discarded for exceeding the tolerances so far.
+    pub fn get_rejected_substeps(&self) -> u64 {
+        self.rejected_substeps
+    }
+
+    // Computes the new state into the last stage and returns the normalized error estimation.
+    fn try_substep(&mut self, step_size: f64) -> f64 {
+        for (stage, coefficients) in A.iter().enumerate().skip(1) {
+            for (i, (body, stage_body)) in self.bodies.iter().zip(&mut self.stage_bodies).enumerate() {
+                let mut position = body.position;
+                let mut velocity = body.velocity;
+
+                for (j, coefficient) in coefficients[..stage].iter().enumerate() {
+                    position += self.velocity_stages[j][i] * (coefficient * step_size);
+                    velocity += self.acceleration_stages[j][i] * (coefficient * step_size);
+                }
+
+                stage_body.position = position;
+                self.velocity_stages[stage][i] = velocity;
+            }
+
+            compute_accelerations(&self.stage_bodies, &mut self.acceleration_stages[stage]);
+        }
+
+        let mut error = 0.0;
+
+        for (i, (body, stage_body)) in self.bodies.iter().zip(&self.stage_bodies).enumerate() {
+            let mut position_error = Vector2 { x: 0.0, y: 0.0 };
+            let mut velocity_error = Vector2 { x: 0.0, y: 0.0 };
+
+            for (j, coefficient) in E.iter().enumerate() {
+                position_error += self.velocity_stages[j][i] * (coefficient * step_size);
+                velocity_error += self.acceleration_stages[j][i] * (coefficient * step_size);
+            }
+
+            let position_scale = self.absolute_tolerance
+                + self.relative_tolerance * body.position.magnitude().max(stage_body.position.magnitude());
+
+            let velocity_scale = self.absolute_tolerance
+                + self.relative_tolerance * body.velocity.magnitude().max(self.velocity_stages[6][i].magnitude());
+
+            error += (position_error / position_scale).magnitude2() + (velocity_error / velocity_scale).magnitude2();
+        }
+
+        (error / (self.bodies.len() * 2) as f64).sqrt()
+    }
+}
+
+impl Universe for DormandPrinceUniverse {
+    fn advance(&mut self, time: f64) {
+        if self.bodies.is_empty() {
+            return;
+        }
+
+        let direction = time.signum();
+        let mut remaining_time = time.abs();
+
+        while remaining_time > 0.0 {
+            let step_size = self.step_size.min(remaining_time);
+            let error = self.try_substep(step_size * direction);
+
+            // No step size can help once the derivatives are not finite, as with coincident bodies.
+
+            assert!(error.is_finite(), "Dormand-Prince error estimation is not finite");
+
+            let scale_factor = (SAFETY_FACTOR * error.powf(-0.2)).clamp(MIN_SCALE_FACTOR, MAX_SCALE_FACTOR);
+
+            if error <= 1.0 {
+                for ((body, stage_body), velocity) in self
+                    .bodies
+                    .iter_mut()
+                    .zip(&self.stage_bodies)
+                    .zip(&self.velocity_stages[6])
+                {
+                    body.position = stage_body.position;
+                    body.velocity = *velocity;
+                }
+
+                // The derivatives of the new state are the first stage of the next substep.
+
+                self.velocity_stages.swap(0, 6);
+                self.acceleration_stages.swap(0, 6);
+
+                remaining_time -= step_size;
+                self.substeps += 1;
+
+                // A substep truncated to fit the remaining time says little about the step size that would work.
+
+                if step_size < self.step_size {
+                    self.step_size = self.step_size.max(step_size * scale_factor);
+                } else {
+                    self.step_size = step_size * scale_factor;
+                }
+            } else {
+                self.rejected_substeps += 1;
+                self.step_size = step_size * scale_factor;
+            }
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DormandPrinceUniverse;
+    use crate::body::Body;
+    use crate::test_utilities::{
+        check_angular_momentum, check_binary_orbit, check_center_of_mass, check_energy, check_momentum,
+        check_reversibility, check_trajectory, get_binary_bodies, get_taylor_bodies,
+    };
+    use crate::universe::Universe;
+    use cgmath::Vector2;
+
+    fn get_taylor_universe() -> DormandPrinceUniverse {
+        DormandPrinceUniverse::new(&get_taylor_bodies(), 1.0e-12, 1.0e-12)
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(get_taylor_universe(), 0.1, 10, 1.0e-6);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        check_center_of_mass(get_taylor_universe(), 0.1, 10, 1.0e-23);
+    }
+
+    #[test]
+    fn momentum() {
+        check_momentum(get_taylor_universe(), 0.1, 10, 1.0e-18);
+    }
+
+    #[test]
+    fn angular_momentun() {
+        check_angular_momentum(get_taylor_universe(), 0.1, 10, 1.0e-8);
+    }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(get_taylor_universe(), 0.1, 10, 1.0e-20);
+    }
+
+    #[test]
+    fn reversibility() {
+        // Not time-symmetric, but accurate enough to return along the same trajectory.
+
+        check_reversibility(get_taylor_universe(), 0.1, 10, 1.0e-20);
+    }
+
+    #[test]
+    fn close_encounter() {
+        let mut universe = DormandPrinceUniverse::new(&get_binary_bodies(0.999), 1.0e-12, 1.0e-12);
+
+        check_binary_orbit(&mut universe, 100, 1.0e-6, 1.0e-10);
+
+        assert!(universe.get_substeps() > 100);
+    }
+
+    #[test]
+    fn empty() {
+        let mut universe = DormandPrinceUniverse::new(&[], 1.0e-12, 1.0e-12);
+
+        universe.advance(0.1);
+
+        assert_eq!(universe.get_substeps(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not finite")]
+    fn coincident_bodies() {
+        let body = Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0));
+
+        DormandPrinceUniverse::new(&[body.clone(), body], 1.0e-12, 1.0e-12).advance(0.1);
+    }
+}
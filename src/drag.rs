@@ -0,0 +1,86 @@
+use cgmath::{InnerSpace, Vector2};
+use std::f64::consts::PI;
+
+/// Velocity dependent force against a static background, which takes energy out of the bodies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Drag {
+    /// Deceleration γ v with the given coefficient γ, as in a viscous medium.
+    Linear(f64),
+    /// Deceleration k |v| v with the given coefficient k, as in a gas at high Reynolds numbers.
+    Quadratic(f64),
+    /// Chandrasekhar's dynamical friction of a body with mass M moving through a background of light particles with a
+    /// Maxwellian velocity distribution, which decelerates it by 4π M ρ ln Λ (erf(X) - 2X / √π exp(-X²)) v / |v|³,
+    /// where X = |v| / (√2 σ).
+    DynamicalFriction {
+        density: f64,
+        velocity_dispersion: f64,
+        coulomb_logarithm: f64,
+    },
+}
+
+// erf(x) - 2x / √π exp(-x²), which is the fraction of the background particles slower than the body. It is computed
+// without cancellation for small x.
+fn slower_fraction(x: f64) -> f64 {
+    if x >= 6.0 {
+        return 1.0;
+    }
+
+    // erf(x) = 2 / √π exp(-x²) Σ 2ⁿ x²ⁿ⁺¹ / (1 × 3 × ⋯ × (2n + 1)), where the first term cancels.
+
+    let x_squared = x * x;
+    let mut term = x * 2.0 * x_squared / 3.0;
+    let mut sum = term;
+    let mut n = 1.0;
+
+    while term > sum * f64::EPSILON {
+        n += 1.0;
+        term *= 2.0 * x_squared / (2.0 * n + 1.0);
+        sum += term;
+    }
+
+    2.0 / PI.sqrt() * (-x_squared).exp() * sum
+}
+
+impl Drag {
+    /// Acceleration of a body with the given mass and velocity, where test particles have zero mass.
+    pub fn acceleration(self, mass: f64, velocity: Vector2<f64>) -> Vector2<f64> {
+        match self {
+            Drag::Linear(coefficient) => velocity * -coefficient,
+            Drag::Quadratic(coefficient) => velocity * (-coefficient * velocity.magnitude()),
+            Drag::DynamicalFriction {
+                density,
+                velocity_dispersion,
+                coulomb_logarithm,
+            } => {
+                let speed = velocity.magnitude();
+
+                if speed == 0.0 {
+                    return velocity;
+                }
+
+                let x = speed / (2.0f64.sqrt() * velocity_dispersion);
+
+                velocity * (-4.0 * PI * mass * density * coulomb_logarithm * slower_fraction(x) / speed.powi(3))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::slower_fraction;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn check_slower_fraction() {
+        assert_eq!(slower_fraction(0.0), 0.0);
+        assert!((slower_fraction(1.0) - (0.842_700_792_949_714_9 - 2.0 / PI.sqrt() * (-1.0f64).exp())).abs() < 1.0e-15);
+        assert!((slower_fraction(3.0) - (0.999_977_909_503_001_4 - 6.0 / PI.sqrt() * (-9.0f64).exp())).abs() < 1.0e-15);
+
+        // Approaches 4x³ / (3√π) for small x, which keeps the friction of slow bodies finite.
+
+        let x = 1.0e-5;
+
+        assert!((slower_fraction(x) / x.powi(3) - 4.0 / (3.0 * PI.sqrt())).abs() < 1.0e-9);
+    }
+}
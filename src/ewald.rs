@@ -0,0 +1,210 @@
+use crate::utilities::minimum_image;
+use cgmath::{InnerSpace, Vector2};
+use std::f64::consts::PI;
+
+// Terms are summed until they fall below erfc(CUTOFF) ≈ 1e-17 relative to the nearest ones.
+const CUTOFF: f64 = 6.0;
+
+// Complementary error function, accurate to about 1e-15 in absolute terms.
+fn erfc(x: f64) -> f64 {
+    if x >= CUTOFF {
+        return 0.0;
+    }
+
+    // erf(x) = 2 / √π exp(-x²) Σ 2ⁿ x²ⁿ⁺¹ / (1 × 3 × ⋯ × (2n + 1)), with only positive terms.
+
+    let x_squared = x * x;
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 0.0;
+
+    while term > sum * f64::EPSILON {
+        n += 1.0;
+        term *= 2.0 * x_squared / (2.0 * n + 1.0);
+        sum += term;
+    }
+
+    1.0 - 2.0 / PI.sqrt() * (-x_squared).exp() * sum
+}
+
+/// Ewald summation of the interaction -m₁ m₂ / r over the periodic images of a square box, which splits the sum into
+/// a short range part summed over nearby images, and a long range part summed over wave vectors. The mean density is
+/// canceled by a uniform background, which makes the sum converge.
+pub struct Ewald {
+    box_size: f64,
+    alpha: f64,
+    image_range: i32,
+    wave_vectors: Vec<(Vector2<f64>, f64)>, // Half of the wave vectors, with their coefficients.
+    self_potential: f64,                    // Potential of a body from its own images.
+}
+
+impl Ewald {
+    /// `alpha` sets the split between the short and the long range parts, and does not change the result.
+    pub fn new(box_size: f64, alpha: f64) -> Ewald {
+        let area = box_size * box_size;
+        let image_range = (CUTOFF / (alpha * box_size) - 0.5).ceil().max(1.0) as i32;
+        let wave_range = (CUTOFF * alpha * box_size / PI).ceil() as i32;
+        let mut wave_vectors = Vec::new();
+
+        for m_y in 0..=wave_range {
+            for m_x in -wave_range..=wave_range {
+                if m_y > 0 || m_x > 0 {
+                    let k = Vector2::new(f64::from(m_x), f64::from(m_y)) * (2.0 * PI / box_size);
+                    let k_magnitude = k.magnitude();
+
+                    // Doubled for the opposite wave vector.
+
+                    wave_vectors.push((k, 4.0 * PI * erfc(k_magnitude / (2.0 * alpha)) / (area * k_magnitude)));
+                }
+            }
+        }
+
+        let short_range_self_potential = (-image_range..=image_range)
+            .flat_map(|n_y| (-image_range..=image_range).map(move |n_x| (n_x, n_y)))
+            .filter(|&n| n != (0, 0))
+            .map(|(n_x, n_y)| {
+                let distance = f64::from(n_x).hypot(f64::from(n_y)) * box_size;
+
+                erfc(alpha * distance) / distance
+            })
+            .sum::<f64>();
+
+        let long_range_self_potential = wave_vectors.iter().map(|(_, c)| c).sum::<f64>();
+
+        Ewald {
+            box_size,
+            alpha,
+            image_range,
+            wave_vectors,
+            self_potential: short_range_self_potential + long_range_self_potential
+                - 2.0 * PI.sqrt() / (alpha * area)
+                - 2.0 * alpha / PI.sqrt(),
+        }
+    }
+
+    fn images(&self, offset: Vector2<f64>) -> impl Iterator<Item = Vector2<f64>> + '_ {
+        let offset = self.minimum_image(offset);
+
+        (-self.image_range..=self.image_range).flat_map(move |n_y| {
+            (-self.image_range..=self.image_range)
+                .map(move |n_x| offset + Vector2::new(f64::from(n_x), f64::from(n_y)) * self.box_size)
+        })
+    }
+
+    /// The nearest periodic image of the offset.
+    pub fn minimum_image(&self, offset: Vector2<f64>) -> Vector2<f64> {
+        minimum_image(offset, self.box_size)
+    }
+
+    /// The periodic counterpart of 1 / r, so the potential energy of two bodies is -m₁ m₂ `potential(offset)`.
+    pub fn potential(&self, offset: Vector2<f64>) -> f64 {
+        let short_range = self
+            .images(offset)
+            .map(|image| {
+                let distance = image.magnitude();
+
+                erfc(self.alpha * distance) / distance
+            })
+            .sum::<f64>();
+
+        let long_range = self
+            .wave_vectors
+            .iter()
+            .map(|(k, c)| c * k.dot(offset).cos())
+            .sum::<f64>();
+
+        short_range + long_range - 2.0 * PI.sqrt() / (self.alpha * self.box_size * self.box_size)
+    }
+
+    /// Gradient of `potential`, so the acceleration of a body towards a body with mass m at offset d is -m
+    /// `gradient(d)`.
+    pub fn gradient(&self, offset: Vector2<f64>) -> Vector2<f64> {
+        let short_range = self
+            .images(offset)
+            .map(|image| {
+                let distance_squared = image.magnitude2();
+                let distance = distance_squared.sqrt();
+                let alpha_distance = self.alpha * distance;
+
+                let magnitude =
+                    erfc(alpha_distance) + 2.0 / PI.sqrt() * alpha_distance * (-alpha_distance * alpha_distance).exp();
+
+                image * (-magnitude / (distance_squared * distance))
+            })
+            .sum::<Vector2<f64>>();
+
+        let long_range = self
+            .wave_vectors
+            .iter()
+            .map(|(k, c)| k * (-c * k.dot(offset).sin()))
+            .sum::<Vector2<f64>>();
+
+        short_range + long_range
+    }
+
+    /// Potential of a body from its own periodic images, so the potential energy of a body is -m² `self_potential` / 2.
+    pub fn self_potential(&self) -> f64 {
+        self.self_potential
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{erfc, Ewald};
+    use cgmath::{InnerSpace, Vector2};
+
+    #[test]
+    fn check_erfc() {
+        assert_eq!(erfc(0.0), 1.0);
+        assert!((erfc(0.5) - 0.479_500_122_186_953_5).abs() < 1.0e-15);
+        assert!((erfc(1.0) - 0.157_299_207_050_285_13).abs() < 1.0e-15);
+        assert!((erfc(3.0) - 2.209_049_699_858_544e-5).abs() < 1.0e-15);
+    }
+
+    #[test]
+    fn independent_of_alpha() {
+        let ewald_1 = Ewald::new(10.0, 0.2);
+        let ewald_2 = Ewald::new(10.0, 0.5);
+
+        for &offset in &[
+            Vector2::new(0.3, -0.1),
+            Vector2::new(4.0, 2.5),
+            Vector2::new(-9.0, 17.0),
+        ] {
+            assert!((ewald_1.potential(offset) - ewald_2.potential(offset)).abs() < 1.0e-12);
+            assert!((ewald_1.gradient(offset) - ewald_2.gradient(offset)).magnitude() < 1.0e-12);
+        }
+
+        assert!((ewald_1.self_potential() - ewald_2.self_potential()).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn gradient() {
+        let ewald = Ewald::new(10.0, 0.3);
+        let h = 1.0e-6;
+
+        for &offset in &[Vector2::new(0.3, -0.1), Vector2::new(4.0, 2.5), Vector2::new(5.0, 5.0)] {
+            let expected_gradient = Vector2::new(
+                ewald.potential(offset + Vector2::new(h, 0.0)) - ewald.potential(offset - Vector2::new(h, 0.0)),
+                ewald.potential(offset + Vector2::new(0.0, h)) - ewald.potential(offset - Vector2::new(0.0, h)),
+            ) / (2.0 * h);
+
+            assert!((ewald.gradient(offset) - expected_gradient).magnitude() < 1.0e-6);
+        }
+
+        // Symmetric at the edges and corners of the box.
+
+        assert!(ewald.gradient(Vector2::new(5.0, 0.0)).magnitude() < 1.0e-12);
+        assert!(ewald.gradient(Vector2::new(5.0, 5.0)).magnitude() < 1.0e-12);
+    }
+
+    #[test]
+    fn short_distance() {
+        // Close bodies interact almost as in open space.
+
+        let ewald = Ewald::new(1000.0, 0.003);
+        let offset = Vector2::new(0.6, 0.8);
+
+        assert!((ewald.gradient(offset) + offset).magnitude() < 1.0e-6);
+    }
+}
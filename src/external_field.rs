@@ -0,0 +1,142 @@
+use cgmath::{InnerSpace, Vector2};
+
+/// Fixed background potential, which acts on bodies in addition to their interactions, but is not affected by them.
+/// The potential energy of a body with mass m is m `potential(position, time)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExternalField {
+    /// Potential -M / r of a point with mass M at the origin.
+    PointMass(f64),
+    /// Potential v² ln(r_c² + r²) / 2 of a dark matter halo, with a flat rotation curve of speed v beyond the core
+    /// radius r_c.
+    LogarithmicHalo { velocity: f64, core_radius: f64 },
+    /// Potential -M / √(R² + (a + √(z² + b²))²) of a disk with mass M, scale length a and scale height b, in its plane
+    /// z = 0.
+    MiyamotoNagai { mass: f64, length: f64, height: f64 },
+    /// Potential -g · x of a uniform acceleration g.
+    Uniform(Vector2<f64>),
+    /// Bar with the given mass and length centered at the origin, rotating counterclockwise with the given angular
+    /// speed from the x axis at time 0. It is modeled as two Plummer spheres at its ends, with softening lengths of
+    /// a quarter of its length. The energy is not conserved, but the Jacobi integral E - Ω L is.
+    RotatingBar { mass: f64, length: f64, pattern_speed: f64 },
+}
+
+// Potential -M / √(r² + ε²) of a Plummer sphere with mass M and softening length ε at the given offset.
+fn plummer_potential(mass: f64, length: f64, offset: Vector2<f64>) -> f64 {
+    -mass / (offset.magnitude2() + length * length).sqrt()
+}
+
+fn plummer_acceleration(mass: f64, length: f64, offset: Vector2<f64>) -> Vector2<f64> {
+    let distance_squared = offset.magnitude2() + length * length;
+
+    offset * (-mass / (distance_squared * distance_squared.sqrt()))
+}
+
+impl ExternalField {
+    // Ends of a rotating bar at the given time.
+    fn bar_ends(length: f64, pattern_speed: f64, time: f64) -> [Vector2<f64>; 2] {
+        let (sin, cos) = (pattern_speed * time).sin_cos();
+        let end = Vector2::new(cos, sin) * (length * 0.5);
+
+        [end, -end]
+    }
+
+    pub fn potential(self, position: Vector2<f64>, time: f64) -> f64 {
+        match self {
+            ExternalField::PointMass(mass) => plummer_potential(mass, 0.0, position),
+            ExternalField::LogarithmicHalo { velocity, core_radius } => {
+                0.5 * velocity * velocity * (core_radius * core_radius + position.magnitude2()).ln()
+            }
+            ExternalField::MiyamotoNagai { mass, length, height } => plummer_potential(mass, length + height, position),
+            ExternalField::Uniform(acceleration) => -acceleration.dot(position),
+            ExternalField::RotatingBar {
+                mass,
+                length,
+                pattern_speed,
+            } => ExternalField::bar_ends(length, pattern_speed, time)
+                .iter()
+                .map(|&end| plummer_potential(mass * 0.5, length * 0.25, position - end))
+                .sum(),
+        }
+    }
+
+    /// The negative gradient of `potential`.
+    pub fn acceleration(self, position: Vector2<f64>, time: f64) -> Vector2<f64> {
+        match self {
+            ExternalField::PointMass(mass) => plummer_acceleration(mass, 0.0, position),
+            ExternalField::LogarithmicHalo { velocity, core_radius } => {
+                position * (-velocity * velocity / (core_radius * core_radius + position.magnitude2()))
+            }
+            ExternalField::MiyamotoNagai { mass, length, height } => {
+                plummer_acceleration(mass, length + height, position)
+            }
+            ExternalField::Uniform(acceleration) => acceleration,
+            ExternalField::RotatingBar {
+                mass,
+                length,
+                pattern_speed,
+            } => ExternalField::bar_ends(length, pattern_speed, time)
+                .iter()
+                .map(|&end| plummer_acceleration(mass * 0.5, length * 0.25, position - end))
+                .sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExternalField;
+    use cgmath::{InnerSpace, Vector2};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn acceleration_is_negative_potential_gradient() {
+        for &field in &[
+            ExternalField::PointMass(3.0),
+            ExternalField::LogarithmicHalo {
+                velocity: 1.5,
+                core_radius: 0.5,
+            },
+            ExternalField::MiyamotoNagai {
+                mass: 3.0,
+                length: 0.8,
+                height: 0.2,
+            },
+            ExternalField::Uniform(Vector2::new(0.3, -0.4)),
+            ExternalField::RotatingBar {
+                mass: 3.0,
+                length: 2.0,
+                pattern_speed: 0.7,
+            },
+        ] {
+            for &position in &[Vector2::new(0.5, 0.2), Vector2::new(-1.0, 1.5), Vector2::new(3.0, -0.1)] {
+                let h = 1.0e-6;
+                let time = 1.3;
+
+                let gradient = Vector2::new(
+                    field.potential(position + Vector2::new(h, 0.0), time)
+                        - field.potential(position - Vector2::new(h, 0.0), time),
+                    field.potential(position + Vector2::new(0.0, h), time)
+                        - field.potential(position - Vector2::new(0.0, h), time),
+                ) / (2.0 * h);
+
+                assert!((field.acceleration(position, time) + gradient).magnitude() < 1.0e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn rotating_bar() {
+        // The bar is symmetric under a half turn, so it repeats with half of its rotation period.
+
+        let field = ExternalField::RotatingBar {
+            mass: 3.0,
+            length: 2.0,
+            pattern_speed: 0.5,
+        };
+
+        let position = Vector2::new(1.2, 0.7);
+
+        assert!((field.potential(position, 0.3) - field.potential(position, 0.3 + 2.0 * PI)).abs() < 1.0e-12);
+        assert!((field.potential(position, 0.3) - field.potential(position, 1.0)).abs() > 1.0e-3);
+    }
+}
@@ -0,0 +1,428 @@
+use crate::body::Body;
+use crate::complex::Complex;
+use crate::forces::pow_negative_one_half;
+use cgmath::{InnerSpace, Vector2};
+
+// Leaf cells are expected to hold about this many bodies.
+const LEAF_BODIES: usize = 16;
+
+// Cells on coarser levels have no well separated cells.
+const MIN_LEVELS: usize = 2;
+
+// Expansions are based on 1 / |z - s| = (z - s)^(-1/2) conj(z - s)^(-1/2), where both factors are expanded as power
+// series, so an expansion of order P has P × P complex coefficients. The coefficient with index (k, l) belongs to the
+// term with the kth power of the holomorphic variable and the lth power of the antiholomorphic variable. With the
+// coefficients as a matrix C, every translation has the form X C X^H.
+//
+// The multipole expansion about a center c is
+//
+//     Σ m / |z - s| = (1 / |z - c|) Σ a(k) a(l) M(k, l) (z - c)^(-k) conj(z - c)^(-l),
+//
+// where M(k, l) = Σ m (s - c)^k conj(s - c)^l, and a(k) = C(2k, k) / 4^k are the coefficients of (1 - x)^(-1/2).
+//
+// The local expansion about a center c is Σ L(p, q) (z - c)^p conj(z - c)^q.
+
+struct Translator {
+    order: usize,
+    multipole_to_local_coefficients: Vec<f64>, // Row p, column k: C(-k - 1/2, p) a(k).
+    binomial_coefficients: Vec<f64>,           // Row n, column k: C(n, k).
+    matrix: Vec<Complex>,                      // Buffer.
+    product: Vec<Complex>,                     // Buffer.
+    powers: Vec<Complex>,                      // Buffer.
+}
+
+fn compute_powers(base: Complex, count: usize, result: &mut Vec<Complex>) {
+    result.clear();
+    result.push(Complex::ONE);
+
+    for i in 1..count {
+        let power = result[i - 1] * base;
+
+        result.push(power);
+    }
+}
+
+impl Translator {
+    fn new(order: usize) -> Translator {
+        let mut multipole_to_local_coefficients = vec![0.0; order * order];
+        let mut binomial_coefficients = vec![0.0; order * order];
+
+        for k in 0..order {
+            // C(-k - 1/2, p) = C(-k - 1/2, p - 1) (-k - 1/2 - (p - 1)) / p.
+
+            let mut coefficient = 1.0;
+
+            for p in 0..order {
+                if p > 0 {
+                    coefficient *= -(k as f64 + p as f64 - 0.5) / p as f64;
+                }
+
+                multipole_to_local_coefficients[p * order + k] = coefficient;
+            }
+        }
+
+        for k in 0..order {
+            // a(k) = |C(-1/2, k)|.
+
+            let a = multipole_to_local_coefficients[k * order].abs();
+
+            for p in 0..order {
+                multipole_to_local_coefficients[p * order + k] *= a;
+            }
+        }
+
+        for n in 0..order {
+            binomial_coefficients[n * order] = 1.0;
+
+            for k in 1..=n {
+                binomial_coefficients[n * order + k] =
+                    binomial_coefficients[(n - 1) * order + k - 1] + binomial_coefficients[(n - 1) * order + k];
+            }
+        }
+
+        Translator {
+            order,
+            multipole_to_local_coefficients,
+            binomial_coefficients,
+            matrix: vec![Complex::ZERO; order * order],
+            product: vec![Complex::ZERO; order * order],
+            powers: Vec::with_capacity(order * 2),
+        }
+    }
+
+    // Sets the matrix to C(row, column) offset^(row - column), which shifts multipole expansions by `offset`. The
+    // transpose shifts local expansions by `offset`.
+    fn set_shift(&mut self, offset: Complex, transpose: bool) {
+        let n = self.order;
+
+        compute_powers(offset, n, &mut self.powers);
+
+        for row in 0..n {
+            for column in 0..n {
+                let value = if column <= row {
+                    self.powers[row - column] * self.binomial_coefficients[row * n + column]
+                } else {
+                    Complex::ZERO
+                };
+
+                if transpose {
+                    self.matrix[column * n + row] = value;
+                } else {
+                    self.matrix[row * n + column] = value;
+                }
+            }
+        }
+    }
+
+    // Sets the matrix to C(-k - 1/2, p) a(k) offset^(-k - p) at row p and column k, which converts multipole expansions
+    // into local expansions, where `offset` is the local expansion center minus the multipole expansion center.
+    fn set_multipole_to_local(&mut self, offset: Complex) {
+        let n = self.order;
+
+        compute_powers(offset.recip(), n * 2, &mut self.powers);
+
+        for p in 0..n {
+            for k in 0..n {
+                self.matrix[p * n + k] = self.powers[k + p] * self.multipole_to_local_coefficients[p * n + k];
+            }
+        }
+    }
+
+    // Computes `target += scale X C X^H`.
+    fn translate(&mut self, source: &[Complex], scale: f64, target: &mut [Complex]) {
+        let n = self.order;
+
+        for i in 0..n {
+            for j in 0..n {
+                self.product[i * n + j] =
+                    (0..n).fold(Complex::ZERO, |sum, k| sum + self.matrix[i * n + k] * source[k * n + j]);
+            }
+        }
+
+        for i in 0..n {
+            for j in 0..n {
+                target[i * n + j] += (0..n).fold(Complex::ZERO, |sum, k| {
+                    sum + self.product[i * n + k] * self.matrix[j * n + k].conj()
+                }) * scale;
+            }
+        }
+    }
+}
+
+/// Fast multipole method over a uniform quadtree.
+pub struct FastMultipoleMethod {
+    translator: Translator,
+    multipoles: Vec<Vec<Complex>>, // Buffer, indexed by level, then cell.
+    locals: Vec<Vec<Complex>>,     // Buffer, indexed by level, then cell.
+    leaf_starts: Vec<usize>,       // Buffer, range starts of leaf cells in `body_indices`.
+    body_indices: Vec<usize>,      // Buffer, sorted by leaf cells.
+    body_cells: Vec<usize>,        // Buffer.
+}
+
+struct Grid {
+    origin: Vector2<f64>,
+    size: f64,
+}
+
+impl Grid {
+    fn cell_center(&self, level: usize, x: usize, y: usize) -> Complex {
+        let cell_size = self.size / (1 << level) as f64;
+
+        Complex::from(self.origin + Vector2::new(x as f64 + 0.5, y as f64 + 0.5) * cell_size)
+    }
+}
+
+fn cell_index(level: usize, x: usize, y: usize) -> usize {
+    (y << level) | x
+}
+
+fn expansion(expansions: &[Complex], cell: usize, order: usize) -> &[Complex] {
+    &expansions[cell * order * order..(cell + 1) * order * order]
+}
+
+fn expansion_mut(expansions: &mut [Complex], cell: usize, order: usize) -> &mut [Complex] {
+    &mut expansions[cell * order * order..(cell + 1) * order * order]
+}
+
+// Offsets of cells in the interaction list relative to the target cell, which are children of the neighbors of the
+// parent cell, but not neighbors of the target cell.
+fn interaction_list(level: usize, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+    let side = 1 << level;
+    let range = move |i: usize| (i & !1).saturating_sub(2)..((i & !1) + 4).min(side);
+
+    range(y).flat_map(move |source_y| {
+        range(x)
+            .filter(move |&source_x| source_x.max(x) - source_x.min(x) > 1 || source_y.max(y) - source_y.min(y) > 1)
+            .map(move |source_x| (source_x, source_y))
+    })
+}
+
+impl FastMultipoleMethod {
+    /// Higher orders give more accurate accelerations with more computations.
+    pub fn new(order: usize) -> FastMultipoleMethod {
+        FastMultipoleMethod {
+            translator: Translator::new(order),
+            multipoles: Vec::new(),
+            locals: Vec::new(),
+            leaf_starts: Vec::new(),
+            body_indices: Vec::new(),
+            body_cells: Vec::new(),
+        }
+    }
+
+    fn prepare(&mut self, bodies: &[Body]) -> (Grid, usize) {
+        let n = self.translator.order;
+        let mut levels = MIN_LEVELS;
+
+        while (1 << (2 * levels)) * LEAF_BODIES < bodies.len() {
+            levels += 1;
+        }
+
+        // Bounding square, slightly enlarged so every body falls strictly inside.
+
+        let (min, max) = bodies.iter().fold(
+            (
+                Vector2::new(f64::INFINITY, f64::INFINITY),
+                Vector2::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), b| {
+                (
+                    Vector2::new(min.x.min(b.position.x), min.y.min(b.position.y)),
+                    Vector2::new(max.x.max(b.position.x), max.y.max(b.position.y)),
+                )
+            },
+        );
+
+        let size = (max.x - min.x).max(max.y - min.y).max(f64::MIN_POSITIVE) * (1.0 + 1.0e-9);
+
+        let grid = Grid {
+            origin: (min + max - Vector2::new(size, size)) * 0.5,
+            size,
+        };
+
+        self.multipoles.resize(levels + 1, Vec::new());
+        self.locals.resize(levels + 1, Vec::new());
+
+        for level in 0..=levels {
+            let count = (n * n) << (2 * level);
+
+            self.multipoles[level].clear();
+            self.multipoles[level].resize(count, Complex::ZERO);
+            self.locals[level].clear();
+            self.locals[level].resize(count, Complex::ZERO);
+        }
+
+        // Sort bodies into leaf cells.
+
+        let side = 1 << levels;
+        let leaf_size = size / side as f64;
+
+        self.body_cells.clear();
+
+        self.body_cells.extend(bodies.iter().map(|b| {
+            let x = (((b.position.x - grid.origin.x) / leaf_size) as usize).min(side - 1);
+            let y = (((b.position.y - grid.origin.y) / leaf_size) as usize).min(side - 1);
+
+            cell_index(levels, x, y)
+        }));
+
+        self.leaf_starts.clear();
+        self.leaf_starts.resize(side * side + 1, 0);
+
+        for &cell in &self.body_cells {
+            self.leaf_starts[cell + 1] += 1;
+        }
+
+        for i in 1..self.leaf_starts.len() {
+            self.leaf_starts[i] += self.leaf_starts[i - 1];
+        }
+
+        self.body_indices.clear();
+        self.body_indices.resize(bodies.len(), 0);
+
+        let mut next_positions = self.leaf_starts.clone();
+
+        for (i, &cell) in self.body_cells.iter().enumerate() {
+            self.body_indices[next_positions[cell]] = i;
+            next_positions[cell] += 1;
+        }
+
+        (grid, levels)
+    }
+
+    fn leaf_bodies(&self, cell: usize) -> &[usize] {
+        &self.body_indices[self.leaf_starts[cell]..self.leaf_starts[cell + 1]]
+    }
+
+    /// Computes the accelerations of all bodies.
+    pub fn compute_accelerations(&mut self, bodies: &[Body], accelerations: &mut [Vector2<f64>]) {
+        let n = self.translator.order;
+        let (grid, levels) = self.prepare(bodies);
+        let side = 1 << levels;
+        let mut powers = Vec::with_capacity(n);
+
+        // Multipole expansions of leaf cells.
+
+        for y in 0..side {
+            for x in 0..side {
+                let cell = cell_index(levels, x, y);
+                let center = grid.cell_center(levels, x, y);
+                let multipole = expansion_mut(&mut self.multipoles[levels], cell, n);
+
+                for &i in &self.body_indices[self.leaf_starts[cell]..self.leaf_starts[cell + 1]] {
+                    compute_powers(Complex::from(bodies[i].position) - center, n, &mut powers);
+
+                    for k in 0..n {
+                        for l in 0..n {
+                            multipole[k * n + l] += powers[k] * powers[l].conj() * bodies[i].mass;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Upward pass, shifting multipole expansions of children to their parents.
+
+        for level in (MIN_LEVELS..levels).rev() {
+            let (parent_multipoles, child_multipoles) = self.multipoles.split_at_mut(level + 1);
+
+            for y in 0..(2 << level) {
+                for x in 0..(2 << level) {
+                    self.translator.set_shift(
+                        grid.cell_center(level + 1, x, y) - grid.cell_center(level, x / 2, y / 2),
+                        false,
+                    );
+
+                    self.translator.translate(
+                        expansion(&child_multipoles[0], cell_index(level + 1, x, y), n),
+                        1.0,
+                        expansion_mut(&mut parent_multipoles[level], cell_index(level, x / 2, y / 2), n),
+                    );
+                }
+            }
+        }
+
+        // Downward pass, converting multipole expansions of well separated cells into local expansions, and shifting
+        // local expansions of parents to their children.
+
+        for level in MIN_LEVELS..=levels {
+            let (parent_locals, child_locals) = self.locals.split_at_mut(level);
+
+            for y in 0..(1 << level) {
+                for x in 0..(1 << level) {
+                    let cell = cell_index(level, x, y);
+                    let center = grid.cell_center(level, x, y);
+                    let local = expansion_mut(&mut child_locals[0], cell, n);
+
+                    if level > MIN_LEVELS {
+                        self.translator
+                            .set_shift(center - grid.cell_center(level - 1, x / 2, y / 2), true);
+
+                        self.translator.translate(
+                            expansion(&parent_locals[level - 1], cell_index(level - 1, x / 2, y / 2), n),
+                            1.0,
+                            local,
+                        );
+                    }
+
+                    for (source_x, source_y) in interaction_list(level, x, y) {
+                        let offset = center - grid.cell_center(level, source_x, source_y);
+
+                        self.translator.set_multipole_to_local(offset);
+
+                        self.translator.translate(
+                            expansion(&self.multipoles[level], cell_index(level, source_x, source_y), n),
+                            offset.norm().recip(),
+                            local,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Evaluate local expansions, and sum the contributions of neighbor cells directly.
+
+        for y in 0..side {
+            for x in 0..side {
+                let cell = cell_index(levels, x, y);
+                let center = grid.cell_center(levels, x, y);
+                let local = expansion(&self.locals[levels], cell, n);
+
+                for &i in self.leaf_bodies(cell) {
+                    let position = bodies[i].position;
+
+                    // The acceleration is the gradient of the potential, which is 2 ∂/∂conj(z) of it.
+
+                    let offset = Complex::from(position) - center;
+
+                    compute_powers(offset, n, &mut powers);
+
+                    let mut acceleration = Complex::ZERO;
+
+                    for p in 0..n {
+                        for q in 1..n {
+                            acceleration += local[p * n + q] * powers[p] * powers[q - 1].conj() * (2 * q) as f64;
+                        }
+                    }
+
+                    let mut acceleration = Vector2::from(acceleration);
+
+                    for neighbor_y in y.saturating_sub(1)..(y + 2).min(side) {
+                        for neighbor_x in x.saturating_sub(1)..(x + 2).min(side) {
+                            for &j in self.leaf_bodies(cell_index(levels, neighbor_x, neighbor_y)) {
+                                if j != i {
+                                    let distance = bodies[j].position - position;
+
+                                    acceleration +=
+                                        distance * (bodies[j].mass * pow_negative_one_half(distance.magnitude2()));
+                                }
+                            }
+                        }
+                    }
+
+                    accelerations[i] = acceleration;
+                }
+            }
+        }
+    }
+}
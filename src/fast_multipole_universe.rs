@@ -0,0 +1,105 @@
+use crate::body::Body;
+use crate::fast_multipole::FastMultipoleMethod;
+use crate::universe::Universe;
+use cgmath::Vector2;
+
+/// Leapfrog universe with accelerations approximated by the fast multipole method in O(N) time.
+pub struct FastMultipoleUniverse {
+    bodies: Vec<Body>,
+    acceleration_buffer: Vec<Vector2<f64>>, // Accelerations of the current positions.
+    method: FastMultipoleMethod,            // Buffer.
+}
+
+impl FastMultipoleUniverse {
+    /// Higher expansion orders give more accurate accelerations with more computations.
+    pub fn new(bodies: &[Body], order: usize) -> FastMultipoleUniverse {
+        let mut method = FastMultipoleMethod::new(order);
+        let mut acceleration_buffer = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        method.compute_accelerations(bodies, &mut acceleration_buffer);
+
+        FastMultipoleUniverse {
+            bodies: bodies.to_vec(),
+            acceleration_buffer,
+            method,
+        }
+    }
+}
+
+impl Universe for FastMultipoleUniverse {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+            body.position += body.velocity * time;
+        }
+
+        self.method
+            .compute_accelerations(&self.bodies, &mut self.acceleration_buffer);
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FastMultipoleUniverse;
+    use crate::fast_multipole::FastMultipoleMethod;
+    use crate::forces;
+    use crate::test_utilities::{check_energy, check_trajectory, get_random_bodies, get_taylor_bodies};
+    use cgmath::{InnerSpace, Vector2};
+
+    fn check_accelerations(order: usize, tolerance: f64) {
+        let bodies = get_random_bodies(1000);
+        let mut expected_accelerations = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+        let mut accelerations = expected_accelerations.clone();
+
+        forces::compute_accelerations(&bodies, &mut expected_accelerations);
+        FastMultipoleMethod::new(order).compute_accelerations(&bodies, &mut accelerations);
+
+        let error = accelerations
+            .iter()
+            .zip(&expected_accelerations)
+            .map(|(a, e)| (a - e).magnitude2())
+            .sum::<f64>();
+
+        let magnitude = expected_accelerations.iter().map(|a| a.magnitude2()).sum::<f64>();
+
+        assert!((error / magnitude).sqrt() < tolerance);
+    }
+
+    #[test]
+    fn accelerations() {
+        check_accelerations(2, 1.0e-2);
+        check_accelerations(4, 1.0e-3);
+        check_accelerations(8, 1.0e-5);
+        check_accelerations(16, 1.0e-8);
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(
+            FastMultipoleUniverse::new(&get_taylor_bodies(), 12),
+            0.001,
+            1000,
+            1.0e-4,
+        );
+    }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(
+            FastMultipoleUniverse::new(&get_taylor_bodies(), 8),
+            0.001,
+            1000,
+            1.0e-12,
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::complex::Complex;
+use std::f64::consts::PI;
+
+/// In-place radix-2 fast Fourier transform, where the length of `data` must be a power of two. The inverse transform is
+/// not normalized.
+pub fn fft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+
+    assert!(n.is_power_of_two());
+
+    // Bit reversal permutation.
+
+    let mut j = 0;
+
+    for i in 1..n {
+        let mut bit = n >> 1;
+
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+
+        j |= bit;
+
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    // Butterflies.
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+
+    while length <= n {
+        let angle = sign * 2.0 * PI / length as f64;
+        let root = Complex::new(angle.cos(), angle.sin());
+
+        for start in (0..n).step_by(length) {
+            let mut twiddle = Complex::ONE;
+
+            for k in 0..length / 2 {
+                let even = data[start + k];
+                let odd = data[start + k + length / 2] * twiddle;
+
+                data[start + k] = even + odd;
+                data[start + k + length / 2] = even - odd;
+                twiddle = twiddle * root;
+            }
+        }
+
+        length <<= 1;
+    }
+}
+
+/// Fast Fourier transform of a row-major square grid with side `size`.
+pub fn fft_2d(data: &mut [Complex], size: usize, inverse: bool, column_buffer: &mut Vec<Complex>) {
+    for row in data.chunks_mut(size) {
+        fft(row, inverse);
+    }
+
+    for x in 0..size {
+        column_buffer.clear();
+        column_buffer.extend((0..size).map(|y| data[y * size + x]));
+
+        fft(column_buffer, inverse);
+
+        for (y, value) in column_buffer.iter().enumerate() {
+            data[y * size + x] = *value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fft, fft_2d};
+    use crate::complex::Complex;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn discrete_fourier_transform() {
+        let data = (0..16)
+            .map(|i| Complex::new((i as f64).sin(), (i * i) as f64 * 0.1))
+            .collect::<Vec<_>>();
+
+        let mut result = data.clone();
+
+        fft(&mut result, false);
+
+        for (k, value) in result.iter().enumerate() {
+            let expected = data.iter().enumerate().fold(Complex::ZERO, |sum, (j, x)| {
+                let angle = -2.0 * PI * (j * k) as f64 / data.len() as f64;
+
+                sum + *x * Complex::new(angle.cos(), angle.sin())
+            });
+
+            assert!((*value - expected).norm() < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let data = (0..64)
+            .map(|i| Complex::new((i as f64).cos(), (i % 7) as f64))
+            .collect::<Vec<_>>();
+
+        let mut result = data.clone();
+        let mut column_buffer = Vec::new();
+
+        fft_2d(&mut result, 8, false, &mut column_buffer);
+        fft_2d(&mut result, 8, true, &mut column_buffer);
+
+        for (value, expected) in result.iter().zip(&data) {
+            assert!((*value * (1.0 / 64.0) - *expected).norm() < 1.0e-12);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::softening::Softening;
+
+/// Pairwise interaction between bodies. Every law couples bodies through the product of their masses, so the potential
+/// energy of two bodies is m₁ m₂ `potential(r²)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ForceLaw {
+    /// Newtonian gravity with potential -1 / r, which is smoothed by the softening of the universe.
+    #[default]
+    InverseSquare,
+    /// Attraction 1 / rⁿ with the given exponent n.
+    InversePower(f64),
+    /// Potential -exp(-r / λ) / r with the given range λ, which is Newtonian well within the range, and vanishes
+    /// exponentially beyond it.
+    Yukawa(f64),
+    /// Potential 4ε ((σ / r)¹² - (σ / r)⁶) with depth ε and length σ, which repels at distances below 2^(1/6) σ, and
+    /// attracts beyond it.
+    LennardJones { depth: f64, length: f64 },
+    /// Potential k r² / 2 with the given stiffness k, as if all bodies were connected by springs of zero rest length.
+    Harmonic(f64),
+}
+
+impl ForceLaw {
+    /// Potential energy per product of masses of two bodies at distance r. Softening only applies to the inverse
+    /// square law.
+    pub fn potential(self, distance_squared: f64, softening: Softening) -> f64 {
+        match self {
+            ForceLaw::InverseSquare => -softening.inverse_distance(distance_squared),
+            ForceLaw::InversePower(exponent) => {
+                if exponent == 1.0 {
+                    0.5 * distance_squared.ln()
+                } else {
+                    -distance_squared.powf((1.0 - exponent) * 0.5) / (exponent - 1.0)
+                }
+            }
+            ForceLaw::Yukawa(range) => {
+                let distance = distance_squared.sqrt();
+
+                -(-distance / range).exp() / distance
+            }
+            ForceLaw::LennardJones { depth, length } => {
+                let power_6 = (length * length / distance_squared).powi(3);
+
+                4.0 * depth * power_6 * (power_6 - 1.0)
+            }
+            ForceLaw::Harmonic(stiffness) => 0.5 * stiffness * distance_squared,
+        }
+    }
+
+    /// Derivative of the potential divided by the distance, so the acceleration of a body towards a body with mass m
+    /// at distance d is m d `attraction(|d|²)`, which is negative for repulsion.
+    pub fn attraction(self, distance_squared: f64, softening: Softening) -> f64 {
+        match self {
+            ForceLaw::InverseSquare => softening.inverse_distance_cubed(distance_squared),
+            ForceLaw::InversePower(exponent) => distance_squared.powf(-(exponent + 1.0) * 0.5),
+            ForceLaw::Yukawa(range) => {
+                let distance = distance_squared.sqrt();
+
+                (-distance / range).exp() * (1.0 + distance / range) / (distance_squared * distance)
+            }
+            ForceLaw::LennardJones { depth, length } => {
+                let power_6 = (length * length / distance_squared).powi(3);
+
+                24.0 * depth * power_6 * (1.0 - 2.0 * power_6) / distance_squared
+            }
+            ForceLaw::Harmonic(stiffness) => stiffness,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ForceLaw;
+    use crate::softening::Softening;
+
+    #[test]
+    fn force_is_potential_gradient() {
+        for &force_law in &[
+            ForceLaw::InverseSquare,
+            ForceLaw::InversePower(1.0),
+            ForceLaw::InversePower(3.5),
+            ForceLaw::Yukawa(0.7),
+            ForceLaw::LennardJones {
+                depth: 2.0,
+                length: 0.5,
+            },
+            ForceLaw::Harmonic(3.0),
+        ] {
+            let mut r: f64 = 0.3;
+
+            while r < 2.0 {
+                let h = 1.0e-6;
+                let potential = |r: f64| force_law.potential(r * r, Softening::None);
+                let gradient = (potential(r + h) - potential(r - h)) / (2.0 * h);
+                let attraction = r * force_law.attraction(r * r, Softening::None);
+
+                assert!((gradient - attraction).abs() < 1.0e-6 * gradient.abs().max(1.0));
+
+                r += 0.01;
+            }
+        }
+    }
+
+    #[test]
+    fn special_cases() {
+        // The inverse square law is the inverse power law with exponent 2.
+
+        for &r in &[0.5, 1.0, 3.0] {
+            let inverse_square = ForceLaw::InverseSquare;
+            let inverse_power = ForceLaw::InversePower(2.0);
+
+            assert!(
+                (inverse_square.potential(r * r, Softening::None) - inverse_power.potential(r * r, Softening::None))
+                    .abs()
+                    < 1.0e-15
+            );
+
+            assert!(
+                (inverse_square.attraction(r * r, Softening::None) - inverse_power.attraction(r * r, Softening::None))
+                    .abs()
+                    < 1.0e-15
+            );
+        }
+
+        // The Lennard-Jones potential has its minimum -ε at 2^(1/6) σ.
+
+        let force_law = ForceLaw::LennardJones {
+            depth: 2.0,
+            length: 0.5,
+        };
+
+        let minimum = 2.0f64.powf(1.0 / 6.0) * 0.5;
+
+        assert!((force_law.potential(minimum * minimum, Softening::None) + 2.0).abs() < 1.0e-12);
+        assert!(force_law.attraction(minimum * minimum, Softening::None).abs() < 1.0e-12);
+    }
+}
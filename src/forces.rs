@@ -0,0 +1,333 @@
+use crate::body::Body;
+use crate::body3::Body3;
+use crate::drag::Drag;
+use crate::external_field::ExternalField;
+use crate::force_law::ForceLaw;
+use crate::softening::Softening;
+use crate::test_particle::TestParticle;
+use cgmath::{InnerSpace, Vector2, Vector3};
+use itertools::Itertools;
+
+pub fn pow_negative_one_half(x: f64) -> f64 {
+    (x.sqrt() * x).recip()
+}
+
+/// Product of the charges of two bodies, which scales their Coulomb interaction. Pairs with a massless body do not
+/// interact, since the massless body could not react to the force it exerts.
+pub fn coulomb_coupling(body_i: &Body, body_j: &Body) -> f64 {
+    if body_i.mass == 0.0 || body_j.mass == 0.0 {
+        0.0
+    } else {
+        body_i.charge * body_j.charge
+    }
+}
+
+pub fn compute_accelerations(bodies: &[Body], accelerations: &mut [Vector2<f64>]) {
+    compute_pairwise_accelerations(bodies, ForceLaw::InverseSquare, Softening::None, accelerations);
+}
+
+pub fn compute_pairwise_accelerations(
+    bodies: &[Body],
+    force_law: ForceLaw,
+    softening: Softening,
+    accelerations: &mut [Vector2<f64>],
+) {
+    compute_pairwise_accelerations_except(bodies, force_law, softening, |_, _| false, accelerations);
+}
+
+/// Computes the accelerations like `compute_pairwise_accelerations`, without the interactions of the excluded pairs of
+/// indices i < j, which universes solving them separately leave out.
+pub fn compute_pairwise_accelerations_except(
+    bodies: &[Body],
+    force_law: ForceLaw,
+    softening: Softening,
+    excluded: impl Fn(usize, usize) -> bool,
+    accelerations: &mut [Vector2<f64>],
+) {
+    for a in accelerations.iter_mut() {
+        *a = Vector2 { x: 0.0, y: 0.0 };
+    }
+
+    for [(i, body_i), (j, body_j)] in bodies.iter().enumerate().array_combinations() {
+        if excluded(i, j) {
+            continue;
+        }
+
+        let distance = body_j.position - body_i.position;
+        let distance_squared = distance.magnitude2();
+        let magnitude = force_law.attraction(distance_squared, softening);
+
+        accelerations[i] += distance * (body_j.mass * magnitude);
+        accelerations[j] -= distance * (body_i.mass * magnitude);
+
+        // Coulomb forces follow the inverse square law regardless of the force law of gravity.
+
+        let charge_product = coulomb_coupling(body_i, body_j);
+
+        if charge_product != 0.0 {
+            let magnitude = charge_product * softening.inverse_distance_cubed(distance_squared);
+
+            accelerations[i] -= distance * (magnitude / body_i.mass);
+            accelerations[j] += distance * (magnitude / body_j.mass);
+        }
+    }
+}
+
+/// Computes the accelerations of test particles in O(N M) time, where N is the number of bodies, and M is the number
+/// of test particles.
+pub fn compute_test_particle_accelerations(
+    bodies: &[Body],
+    force_law: ForceLaw,
+    softening: Softening,
+    test_particles: &[TestParticle],
+    accelerations: &mut [Vector2<f64>],
+) {
+    for (test_particle, acceleration) in test_particles.iter().zip(accelerations.iter_mut()) {
+        *acceleration = bodies
+            .iter()
+            .map(|body| {
+                let distance = body.position - test_particle.position;
+
+                distance * (body.mass * force_law.attraction(distance.magnitude2(), softening))
+            })
+            .sum();
+    }
+}
+
+/// Adds the first post-Newtonian corrections of the Einstein-Infeld-Hoffmann equations to the accelerations, which
+/// make orbits precess. The buffer keeps the Newtonian accelerations and potentials of the bodies.
+pub fn add_post_newtonian_accelerations(
+    bodies: &[Body],
+    speed_of_light: f64,
+    buffer: &mut Vec<(Vector2<f64>, f64)>,
+    accelerations: &mut [Vector2<f64>],
+) {
+    buffer.clear();
+    buffer.resize(bodies.len(), (Vector2 { x: 0.0, y: 0.0 }, 0.0));
+
+    for [(i, body_i), (j, body_j)] in bodies.iter().enumerate().array_combinations() {
+        let distance = body_j.position - body_i.position;
+        let distance_squared = distance.magnitude2();
+        let inverse_distance = distance_squared.sqrt().recip();
+        let magnitude = pow_negative_one_half(distance_squared);
+
+        buffer[i].0 += distance * (body_j.mass * magnitude);
+        buffer[j].0 -= distance * (body_i.mass * magnitude);
+        buffer[i].1 += body_j.mass * inverse_distance;
+        buffer[j].1 += body_i.mass * inverse_distance;
+    }
+
+    let inverse_c_squared = (speed_of_light * speed_of_light).recip();
+
+    for (i, (body_i, acceleration)) in bodies.iter().zip(accelerations.iter_mut()).enumerate() {
+        let mut correction = Vector2 { x: 0.0, y: 0.0 };
+
+        for (j, body_j) in bodies.iter().enumerate() {
+            if i != j {
+                let (acceleration_j, potential_j) = buffer[j];
+                let distance = body_j.position - body_i.position;
+                let distance_squared = distance.magnitude2();
+                let inverse_distance = distance_squared.sqrt().recip();
+                let magnitude = body_j.mass * pow_negative_one_half(distance_squared);
+                let radial_velocity_j = distance.dot(body_j.velocity) * inverse_distance;
+
+                let factor = -4.0 * buffer[i].1 - potential_j
+                    + body_i.velocity.magnitude2()
+                    + 2.0 * body_j.velocity.magnitude2()
+                    - 4.0 * body_i.velocity.dot(body_j.velocity)
+                    - 1.5 * radial_velocity_j * radial_velocity_j
+                    + 0.5 * distance.dot(acceleration_j);
+
+                correction += distance * (magnitude * factor);
+
+                correction -= (body_i.velocity - body_j.velocity)
+                    * (magnitude * distance.dot(body_i.velocity * 4.0 - body_j.velocity * 3.0));
+
+                correction += acceleration_j * (3.5 * body_j.mass * inverse_distance);
+            }
+        }
+
+        *acceleration += correction * inverse_c_squared;
+    }
+}
+
+/// Adds the 2.5 post-Newtonian radiation reaction of the relative motion of every pair of bodies, as if the pair were
+/// an isolated binary, which makes binaries lose energy and spiral in. Returns the power radiated by all pairs.
+pub fn add_radiation_reaction_accelerations(
+    bodies: &[Body],
+    speed_of_light: f64,
+    accelerations: &mut [Vector2<f64>],
+) -> f64 {
+    let mut power = 0.0;
+
+    for [(i, body_i), (j, body_j)] in bodies.iter().enumerate().array_combinations() {
+        // Relative acceleration in harmonic coordinates, in terms of the total mass, the symmetric mass ratio, and the
+        // relative position and velocity.
+
+        let mass = body_i.mass + body_j.mass;
+        let mass_ratio = body_i.mass * body_j.mass / (mass * mass);
+        let offset = body_i.position - body_j.position;
+        let velocity = body_i.velocity - body_j.velocity;
+        let distance = offset.magnitude();
+        let direction = offset / distance;
+        let radial_velocity = direction.dot(velocity);
+        let speed_squared = velocity.magnitude2();
+        let potential = mass / distance;
+
+        let relative_acceleration = (direction * ((3.0 * speed_squared + 17.0 / 3.0 * potential) * radial_velocity)
+            - velocity * (speed_squared + 3.0 * potential))
+            * (1.6 * mass_ratio * mass * mass / (speed_of_light.powi(5) * distance.powi(3)));
+
+        accelerations[i] += relative_acceleration * (body_j.mass / mass);
+        accelerations[j] -= relative_acceleration * (body_i.mass / mass);
+
+        power -= mass * mass_ratio * relative_acceleration.dot(velocity);
+    }
+
+    power
+}
+
+/// Adds the accelerations of drags to the accelerations of bodies with the given masses and velocities. Returns the
+/// power dissipated by the drags.
+pub fn add_drag_accelerations(
+    drags: &[Drag],
+    masses_and_velocities: impl Iterator<Item = (f64, Vector2<f64>)>,
+    accelerations: &mut [Vector2<f64>],
+) -> f64 {
+    let mut power = 0.0;
+
+    if !drags.is_empty() {
+        for ((mass, velocity), acceleration) in masses_and_velocities.zip(accelerations.iter_mut()) {
+            for drag in drags {
+                let drag_acceleration = drag.acceleration(mass, velocity);
+
+                *acceleration += drag_acceleration;
+                power -= mass * drag_acceleration.dot(velocity);
+            }
+        }
+    }
+
+    power
+}
+
+/// Adds the accelerations of external fields at the given time to the accelerations of the given positions.
+pub fn add_external_accelerations(
+    external_fields: &[ExternalField],
+    time: f64,
+    positions: impl Iterator<Item = Vector2<f64>>,
+    accelerations: &mut [Vector2<f64>],
+) {
+    if !external_fields.is_empty() {
+        for (position, acceleration) in positions.zip(accelerations.iter_mut()) {
+            for external_field in external_fields {
+                *acceleration += external_field.acceleration(position, time);
+            }
+        }
+    }
+}
+
+pub fn compute_accelerations3(bodies: &[Body3], accelerations: &mut [Vector3<f64>]) {
+    for a in accelerations.iter_mut() {
+        *a = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+    }
+
+    for [(i, body_i), (j, body_j)] in bodies.iter().enumerate().array_combinations() {
+        let distance = body_j.position - body_i.position;
+        let magnitude = pow_negative_one_half(distance.magnitude2());
+
+        accelerations[i] += distance * (body_j.mass * magnitude);
+        accelerations[j] -= distance * (body_i.mass * magnitude);
+    }
+}
+
+pub fn compute_accelerations_and_jerks(
+    bodies: &[Body],
+    accelerations: &mut [Vector2<f64>],
+    jerks: &mut [Vector2<f64>],
+) {
+    for (a, j) in accelerations.iter_mut().zip(jerks.iter_mut()) {
+        *a = Vector2 { x: 0.0, y: 0.0 };
+        *j = Vector2 { x: 0.0, y: 0.0 };
+    }
+
+    for [(i, body_i), (j, body_j)] in bodies.iter().enumerate().array_combinations() {
+        let distance = body_j.position - body_i.position;
+        let velocity = body_j.velocity - body_i.velocity;
+        let distance_squared = distance.magnitude2();
+        let magnitude = pow_negative_one_half(distance_squared);
+        let jerk = (velocity - distance * (3.0 * distance.dot(velocity) / distance_squared)) * magnitude;
+
+        // Coulomb forces have the same form as gravity, with the opposite sign for like charges.
+
+        let charge_product = coulomb_coupling(body_i, body_j);
+
+        let (coupling_i, coupling_j) = if charge_product == 0.0 {
+            (body_j.mass, body_i.mass)
+        } else {
+            (
+                body_j.mass - charge_product / body_i.mass,
+                body_i.mass - charge_product / body_j.mass,
+            )
+        };
+
+        accelerations[i] += distance * (coupling_i * magnitude);
+        accelerations[j] -= distance * (coupling_j * magnitude);
+        jerks[i] += jerk * coupling_i;
+        jerks[j] -= jerk * coupling_j;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_accelerations, compute_accelerations_and_jerks, pow_negative_one_half};
+    use crate::body::Body;
+    use cgmath::Vector2;
+
+    #[test]
+    fn check_pow_negative_one_half() {
+        let mut i = 1.0e-6;
+
+        while i < 1.0e6 {
+            assert!((pow_negative_one_half(i) - i.powf(-1.5)).abs() < 1e-6);
+
+            i *= 1.0001;
+        }
+    }
+
+    #[test]
+    fn massless_bodies() {
+        // Massless bodies are attracted by the others, and neither feel nor exert Coulomb forces.
+
+        let bodies = [
+            Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0)).with_charge(0.5),
+            Body::new(0.0, Vector2::new(2.0, 0.0), Vector2::new(0.0, 1.0)).with_charge(0.5),
+            Body::new(0.0, Vector2::new(0.0, 4.0), Vector2::new(1.0, 0.0)),
+        ];
+
+        let mut accelerations = vec![Vector2::new(0.0, 0.0); bodies.len()];
+        let mut jerks = vec![Vector2::new(0.0, 0.0); bodies.len()];
+
+        compute_accelerations(&bodies, &mut accelerations);
+
+        assert_eq!(
+            accelerations,
+            [
+                Vector2::new(0.0, 0.0),
+                Vector2::new(-0.25, 0.0),
+                Vector2::new(0.0, -0.0625)
+            ]
+        );
+
+        compute_accelerations_and_jerks(&bodies, &mut accelerations, &mut jerks);
+
+        assert_eq!(
+            accelerations,
+            [
+                Vector2::new(0.0, 0.0),
+                Vector2::new(-0.25, 0.0),
+                Vector2::new(0.0, -0.0625)
+            ]
+        );
+        assert!(jerks.iter().all(|jerk| jerk.x.is_finite() && jerk.y.is_finite()));
+    }
+}
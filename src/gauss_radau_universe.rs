@@ -0,0 +1,463 @@
+use crate::body::Body;
+use crate::forces::compute_accelerations;
+use crate::universe::Universe;
+use cgmath::{InnerSpace, Vector2};
+
+// Gauss-Radau spacings of the substeps within a step, see <https://doi.org/10.1093/mnras/stu2164>.
+const SPACINGS: [f64; 8] = [
+    0.0,
+    0.056_262_560_536_922_15,
+    0.180_240_691_736_892_36,
+    0.352_624_717_113_169_6,
+    0.547_153_626_330_555_4,
+    0.734_210_177_215_410_5,
+    0.885_320_946_839_095_8,
+    0.977_520_613_561_287_5,
+];
+
+// Predictor-corrector iterations stop at this relative change of the highest order coefficients.
+const CONVERGENCE_TOLERANCE: f64 = 1.0e-16;
+const MAX_ITERATIONS: usize = 12;
+
+// Steps are rejected when the next step size would be this much smaller, and grow at most by its inverse.
+const SAFETY_FACTOR: f64 = 0.25;
+
+/// IAS15 universe, which integrates the accelerations as polynomials of degree 7 through the Gauss-Radau substeps of
+/// each step, converged by predictor-corrector iterations. The step size keeps the highest order coefficient at the
+/// given tolerance relative to the accelerations, where a tolerance of 10⁻⁹ makes the error at each step smaller than
+/// the rounding error. Positions and velocities are updated with compensated summation, so the energy error stays at
+/// machine precision over long runs.
+pub struct GaussRadauUniverse {
+    bodies: Vec<Body>,
+    tolerance: f64,
+    step_size: f64,                                // State.
+    previous_step_size: f64,                       // State.
+    previous_coefficients: [Vec<Vector2<f64>>; 7], // State.
+    position_compensations: Vec<Vector2<f64>>,     // State.
+    velocity_compensations: Vec<Vector2<f64>>,     // State.
+    steps: u64,                                    // Statistics.
+    rejected_steps: u64,                           // Statistics.
+    iterations: u64,                               // Statistics.
+    divided_difference_weights: [[f64; 8]; 8],     // Pre-calculated.
+    newton_to_monomial: [[f64; 7]; 7],             // Pre-calculated.
+    coefficients: [Vec<Vector2<f64>>; 7],          // Buffer.
+    divided_differences: [Vec<Vector2<f64>>; 7],   // Buffer.
+    acceleration_substeps: [Vec<Vector2<f64>>; 8], // Buffer.
+    scratch_bodies: Vec<Body>,                     // Buffer.
+}
+
+// Maximum of two values, which is NaN if either is, unlike f64::max.
+fn propagating_max(a: f64, b: f64) -> f64 {
+    if a.is_nan() || a >= b {
+        a
+    } else {
+        b
+    }
+}
+
+// Adds an increment to a value, and keeps the rounding error in the compensation for the next increment.
+fn compensated_add(value: &mut Vector2<f64>, compensation: &mut Vector2<f64>, increment: Vector2<f64>) {
+    let corrected_increment = increment - *compensation;
+    let sum = *value + corrected_increment;
+
+    *compensation = (sum - *value) - corrected_increment;
+    *value = sum;
+}
+
+impl GaussRadauUniverse {
+    pub fn new(bodies: &[Body], tolerance: f64) -> GaussRadauUniverse {
+        assert!(tolerance > 0.0, "Gauss-Radau tolerance is not positive");
+
+        let zeros = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        // The divided difference of the accelerations at the substeps 0 to n is Σ wₙⱼ aⱼ.
+
+        let mut divided_difference_weights = [[0.0; 8]; 8];
+
+        for (n, weights) in divided_difference_weights.iter_mut().enumerate() {
+            for (j, weight) in weights[..=n].iter_mut().enumerate() {
+                *weight = (0..=n)
+                    .filter(|&i| i != j)
+                    .map(|i| SPACINGS[j] - SPACINGS[i])
+                    .product::<f64>()
+                    .recip();
+            }
+        }
+
+        // The accelerations are a0 + Σ gₖ h (h - h₁) ⋯ (h - hₖ) in Newton form, and a0 + Σ bₘ hᵐ⁺¹ in monomial form,
+        // where bₘ = Σ cₖₘ gₖ.
+
+        let mut newton_to_monomial = [[0.0; 7]; 7];
+        let mut polynomial = [0.0; 8];
+
+        polynomial[0] = 1.0;
+
+        for (k, row) in newton_to_monomial.iter_mut().enumerate() {
+            if k > 0 {
+                for m in (0..=k).rev() {
+                    polynomial[m] = if m > 0 { polynomial[m - 1] } else { 0.0 } - SPACINGS[k] * polynomial[m];
+                }
+            }
+
+            row.copy_from_slice(&polynomial[..7]);
+        }
+
+        let mut acceleration_substeps: [Vec<Vector2<f64>>; 8] = Default::default();
+
+        for accelerations in &mut acceleration_substeps {
+            *accelerations = zeros.clone();
+        }
+
+        // The first substep always holds the accelerations of the current positions.
+
+        compute_accelerations(bodies, &mut acceleration_substeps[0]);
+
+        GaussRadauUniverse {
+            bodies: bodies.to_vec(),
+            tolerance,
+            step_size: f64::INFINITY,
+            previous_step_size: 0.0,
+            previous_coefficients: [(); 7].map(|_| zeros.clone()),
+            position_compensations: zeros.clone(),
+            velocity_compensations: zeros.clone(),
+            steps: 0,
+            rejected_steps: 0,
+            iterations: 0,
+            divided_difference_weights,
+            newton_to_monomial,
+            coefficients: [(); 7].map(|_| zeros.clone()),
+            divided_differences: [(); 7].map(|_| zeros.clone()),
+            acceleration_substeps,
+            scratch_bodies: bodies.to_vec(),
+        }
+    }
+
+    pub fn get_tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Number of accepted steps taken so far.
+    pub fn get_steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Number of steps discarded for a too large step size so far.
+    pub fn get_rejected_steps(&self) -> u64 {
+        self.rejected_steps
+    }
+
+    /// Number of predictor-corrector iterations of accepted and rejected steps so far.
+    pub fn get_iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    // Predicts the coefficients of a step with the given size by extending the polynomials of the previous step.
+    fn predict_coefficients(&mut self, step_size: f64) {
+        let n = self.bodies.len();
+
+        if self.previous_step_size == 0.0 {
+            for coefficients in &mut self.coefficients {
+                coefficients.iter_mut().for_each(|b| *b = Vector2 { x: 0.0, y: 0.0 });
+            }
+        } else {
+            // With h = 1 + q h', each bₖ (1 + q h')ᵏ⁺¹ contributes binomial(k + 1, m + 1) qᵐ⁺¹ bₖ to b'ₘ.
+
+            let ratio = step_size / self.previous_step_size;
+
+            for m in 0..7 {
+                for i in 0..n {
+                    let mut coefficient = Vector2 { x: 0.0, y: 0.0 };
+                    let mut binomial = 1.0;
+
+                    for k in m..7 {
+                        coefficient += self.previous_coefficients[k][i] * binomial;
+                        binomial = binomial * (k + 2) as f64 / (k + 1 - m) as f64;
+                    }
+
+                    self.coefficients[m][i] = coefficient * ratio.powi(m as i32 + 1);
+                }
+            }
+        }
+
+        // The Newton form follows by back substitution, since cₖₖ = 1.
+
+        for k in (0..7).rev() {
+            for i in 0..n {
+                let mut difference = self.coefficients[k][i];
+
+                for j in k + 1..7 {
+                    difference -= self.divided_differences[j][i] * self.newton_to_monomial[j][k];
+                }
+
+                self.divided_differences[k][i] = difference;
+            }
+        }
+    }
+
+    // Computes the coefficients of a step with the given size, and returns the highest order coefficient relative to
+    // the accelerations.
+    fn try_step(&mut self, step_size: f64) -> f64 {
+        self.predict_coefficients(step_size);
+
+        let mut previous_change = f64::INFINITY;
+
+        for iteration in 0..MAX_ITERATIONS {
+            let mut change: f64 = 0.0;
+            let mut max_acceleration: f64 = 0.0;
+
+            self.iterations += 1;
+
+            for (substep, &h) in SPACINGS.iter().enumerate().skip(1) {
+                for (i, (body, scratch_body)) in self.bodies.iter().zip(&mut self.scratch_bodies).enumerate() {
+                    let mut displacement = self.acceleration_substeps[0][i] * (h * h * 0.5);
+
+                    for (k, coefficients) in self.coefficients.iter().enumerate() {
+                        displacement += coefficients[i] * (h.powi(k as i32 + 3) / ((k + 2) * (k + 3)) as f64);
+                    }
+
+                    scratch_body.position =
+                        body.position + body.velocity * (step_size * h) + displacement * (step_size * step_size);
+                }
+
+                let (previous_substeps, current_substeps) = self.acceleration_substeps.split_at_mut(substep);
+
+                compute_accelerations(&self.scratch_bodies, &mut current_substeps[0]);
+
+                for i in 0..self.bodies.len() {
+                    // The weights sum to zero, so differences to the first substep avoid cancellation.
+
+                    let initial_acceleration = previous_substeps[0][i];
+
+                    let mut divided_difference = (current_substeps[0][i] - initial_acceleration)
+                        * self.divided_difference_weights[substep][substep];
+
+                    for (accelerations, weight) in previous_substeps
+                        .iter()
+                        .zip(&self.divided_difference_weights[substep])
+                        .skip(1)
+                    {
+                        divided_difference += (accelerations[i] - initial_acceleration) * *weight;
+                    }
+
+                    if substep == 7 {
+                        change = change.max((divided_difference - self.divided_differences[6][i]).magnitude());
+                        max_acceleration = max_acceleration.max(current_substeps[0][i].magnitude());
+                    }
+
+                    self.divided_differences[substep - 1][i] = divided_difference;
+
+                    for m in 0..substep {
+                        let mut coefficient = Vector2 { x: 0.0, y: 0.0 };
+
+                        for k in m..7 {
+                            coefficient += self.divided_differences[k][i] * self.newton_to_monomial[k][m];
+                        }
+
+                        self.coefficients[m][i] = coefficient;
+                    }
+                }
+            }
+
+            if max_acceleration > 0.0 {
+                change /= max_acceleration;
+            }
+
+            // Stops when converged, or when the rounding errors make the coefficients oscillate.
+
+            if change < CONVERGENCE_TOLERANCE || (iteration > 1 && change >= previous_change) {
+                break;
+            }
+
+            previous_change = change;
+        }
+
+        let max_coefficient = self.coefficients[6]
+            .iter()
+            .map(|b| b.magnitude())
+            .fold(0.0, propagating_max);
+
+        let max_acceleration = self.acceleration_substeps[7]
+            .iter()
+            .map(|a| a.magnitude())
+            .fold(0.0, propagating_max);
+
+        if max_acceleration == 0.0 {
+            0.0
+        } else {
+            max_coefficient / max_acceleration
+        }
+    }
+}
+
+impl Universe for GaussRadauUniverse {
+    fn advance(&mut self, time: f64) {
+        let direction = time.signum();
+        let mut remaining_time = time.abs();
+
+        while remaining_time > 0.0 {
+            let step_size = self.step_size.min(remaining_time);
+            let signed_step_size = step_size * direction;
+            let error = self.try_step(signed_step_size);
+
+            // No step size can help once the derivatives are not finite, as with coincident bodies.
+
+            assert!(error.is_finite(), "Gauss-Radau error estimation is not finite");
+
+            let scale_factor = if error > 0.0 {
+                (self.tolerance / error).powf(1.0 / 7.0).min(SAFETY_FACTOR.recip())
+            } else {
+                SAFETY_FACTOR.recip()
+            };
+
+            if scale_factor >= SAFETY_FACTOR {
+                for (i, body) in self.bodies.iter_mut().enumerate() {
+                    let acceleration = self.acceleration_substeps[0][i];
+                    let mut displacement = acceleration * 0.5;
+                    let mut velocity_change = acceleration;
+
+                    for (k, coefficients) in self.coefficients.iter().enumerate() {
+                        displacement += coefficients[i] / ((k + 2) * (k + 3)) as f64;
+                        velocity_change += coefficients[i] / (k + 2) as f64;
+                    }
+
+                    let position_increment =
+                        body.velocity * signed_step_size + displacement * (signed_step_size * signed_step_size);
+
+                    compensated_add(
+                        &mut body.position,
+                        &mut self.position_compensations[i],
+                        position_increment,
+                    );
+
+                    compensated_add(
+                        &mut body.velocity,
+                        &mut self.velocity_compensations[i],
+                        velocity_change * signed_step_size,
+                    );
+                }
+
+                // The accelerations of the new state are the first substep of the next step, and the coefficients
+                // predict the next step.
+
+                compute_accelerations(&self.bodies, &mut self.acceleration_substeps[0]);
+
+                std::mem::swap(&mut self.previous_coefficients, &mut self.coefficients);
+                self.previous_step_size = signed_step_size;
+
+                remaining_time -= step_size;
+                self.steps += 1;
+
+                // A step truncated to fit the remaining time says little about the step size that would work.
+
+                if step_size < self.step_size {
+                    self.step_size = self.step_size.max(step_size * scale_factor);
+                } else {
+                    self.step_size = step_size * scale_factor;
+                }
+            } else {
+                self.rejected_steps += 1;
+                self.step_size = step_size * scale_factor;
+
+                assert!(self.step_size > 0.0, "Gauss-Radau step size underflowed");
+            }
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GaussRadauUniverse;
+    use crate::body::Body;
+    use crate::test_utilities::{
+        check_angular_momentum, check_binary_orbit, check_center_of_mass, check_energy, check_momentum,
+        check_reversibility, check_trajectory, get_binary_bodies, get_taylor_bodies,
+    };
+    use crate::universe::Universe;
+    use cgmath::{InnerSpace, Vector2};
+    use std::f64::consts::{PI, SQRT_2};
+
+    fn get_taylor_universe() -> GaussRadauUniverse {
+        GaussRadauUniverse::new(&get_taylor_bodies(), 1.0e-9)
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(get_taylor_universe(), 0.1, 10, 1.0e-9);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        check_center_of_mass(get_taylor_universe(), 0.1, 10, 1.0e-23);
+    }
+
+    #[test]
+    fn momentum() {
+        check_momentum(get_taylor_universe(), 0.1, 10, 1.0e-18);
+    }
+
+    #[test]
+    fn angular_momentun() {
+        check_angular_momentum(get_taylor_universe(), 0.1, 10, 1.0e-8);
+    }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(get_taylor_universe(), 0.1, 10, 1.0e-20);
+    }
+
+    #[test]
+    fn reversibility() {
+        // Not time-symmetric, but accurate enough to return along the same trajectory.
+
+        check_reversibility(get_taylor_universe(), 0.1, 10, 1.0e-20);
+    }
+
+    #[test]
+    fn close_encounter() {
+        let mut universe = GaussRadauUniverse::new(&get_binary_bodies(0.999), 1.0e-9);
+
+        check_binary_orbit(&mut universe, 100, 1.0e-12, 1.0e-20);
+
+        assert!(universe.get_steps() > 100);
+        assert!(universe.get_rejected_steps() > 0);
+        assert!(universe.get_iterations() > 2 * (universe.get_steps() + universe.get_rejected_steps()));
+    }
+
+    #[test]
+    fn long_term_energy() {
+        // A hundred orbits of an eccentric binary keep the energy to a few rounding errors.
+
+        let mut universe = GaussRadauUniverse::new(&get_binary_bodies(0.5), 1.0e-9);
+        let initial_position = universe.get_bodies()[0].position;
+        let e0 = universe.get_energy();
+
+        for _ in 0..100 {
+            universe.advance(PI * SQRT_2);
+        }
+
+        assert!(((universe.get_energy() - e0) / e0).abs() < 1.0e-14);
+        assert!((universe.get_bodies()[0].position - initial_position).magnitude() < 1.0e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "not positive")]
+    fn zero_tolerance() {
+        GaussRadauUniverse::new(&get_taylor_bodies(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not finite")]
+    fn coincident_bodies() {
+        let body = Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0));
+
+        GaussRadauUniverse::new(&[body.clone(), body], 1.0e-9).advance(0.1);
+    }
+}
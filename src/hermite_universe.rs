@@ -0,0 +1,245 @@
+use crate::body::Body;
+use crate::forces::compute_accelerations_and_jerks;
+use crate::universe::Universe;
+use cgmath::{InnerSpace, Vector2};
+use itertools::Itertools;
+
+/// Fourth order Hermite predictor-corrector universe with a shared step size chosen by the Aarseth criterion, see
+/// <https://doi.org/10.1007/978-94-009-6290-4_23>.
+pub struct HermiteUniverse {
+    bodies: Vec<Body>,
+    accuracy: f64,
+    accelerations: Vec<Vector2<f64>>,
+    jerks: Vec<Vector2<f64>>,
+    step_size: f64,                       // State.
+    steps: u64,                           // Statistics.
+    predicted_bodies: Vec<Body>,          // Buffer.
+    new_accelerations: Vec<Vector2<f64>>, // Buffer.
+    new_jerks: Vec<Vector2<f64>>,         // Buffer.
+}
+
+impl HermiteUniverse {
+    pub fn new(bodies: &[Body], accuracy: f64) -> HermiteUniverse {
+        let mut accelerations = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+        let mut jerks = accelerations.clone();
+
+        compute_accelerations_and_jerks(bodies, &mut accelerations, &mut jerks);
+
+        // Higher derivatives of the accelerations are unknown for the first step, which is the accuracy times the time
+        // scale |a| / |j| instead. Where all jerks vanish, as for bodies at rest, the free fall time scale √(r / |a|)
+        // across the extent r of the bodies takes its place.
+
+        let step_size = accelerations
+            .iter()
+            .zip(&jerks)
+            .map(|(a, j)| accuracy * a.magnitude() / j.magnitude())
+            .fold(f64::INFINITY, f64::min);
+
+        let step_size = if step_size.is_finite() {
+            step_size
+        } else {
+            let extent = bodies
+                .iter()
+                .array_combinations()
+                .map(|[body_i, body_j]| (body_j.position - body_i.position).magnitude())
+                .fold(0.0, f64::max);
+
+            accelerations
+                .iter()
+                .map(|a| accuracy * (extent / a.magnitude()).sqrt())
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        HermiteUniverse {
+            bodies: bodies.to_vec(),
+            accuracy,
+            new_accelerations: accelerations.clone(),
+            new_jerks: jerks.clone(),
+            accelerations,
+            jerks,
+            step_size,
+            steps: 0,
+            predicted_bodies: bodies.to_vec(),
+        }
+    }
+
+    /// Number of steps taken so far.
+    pub fn get_steps(&self) -> u64 {
+        self.steps
+    }
+
+    // Takes a step and returns the step size the Aarseth criterion suggests for the next one.
+    fn step(&mut self, step_size: f64) -> f64 {
+        let dt = step_size;
+        let dt2 = dt * dt;
+
+        // Predict.
+
+        for (((body, predicted_body), a), j) in self
+            .bodies
+            .iter()
+            .zip(&mut self.predicted_bodies)
+            .zip(&self.accelerations)
+            .zip(&self.jerks)
+        {
+            predicted_body.position = body.position + body.velocity * dt + a * (dt2 / 2.0) + j * (dt2 * dt / 6.0);
+            predicted_body.velocity = body.velocity + a * dt + j * (dt2 / 2.0);
+        }
+
+        // Evaluate.
+
+        compute_accelerations_and_jerks(&self.predicted_bodies, &mut self.new_accelerations, &mut self.new_jerks);
+
+        // Correct.
+
+        let mut next_step_size = f64::INFINITY;
+
+        for (((body, (a0, j0)), a1), j1) in self
+            .bodies
+            .iter_mut()
+            .zip(self.accelerations.iter().zip(&self.jerks))
+            .zip(&self.new_accelerations)
+            .zip(&self.new_jerks)
+        {
+            let velocity = body.velocity + (a0 + a1) * (dt / 2.0) + (j0 - j1) * (dt2 / 12.0);
+
+            body.position += (body.velocity + velocity) * (dt / 2.0) + (a0 - a1) * (dt2 / 12.0);
+            body.velocity = velocity;
+
+            // Higher derivatives from the Hermite interpolation, evaluated at the end of the step.
+
+            let a3 = ((a0 - a1) * 12.0 + (j0 + j1) * (6.0 * dt)) / (dt2 * dt);
+            let a2 = ((a1 - a0) * 6.0 - (j0 * 4.0 + j1 * 2.0) * dt) / dt2 + a3 * dt;
+
+            let numerator = a1.magnitude() * a2.magnitude() + j1.magnitude2();
+            let denominator = j1.magnitude() * a3.magnitude() + a2.magnitude2();
+
+            next_step_size = next_step_size.min((self.accuracy * numerator / denominator).sqrt());
+        }
+
+        std::mem::swap(&mut self.accelerations, &mut self.new_accelerations);
+        std::mem::swap(&mut self.jerks, &mut self.new_jerks);
+
+        self.steps += 1;
+
+        next_step_size
+    }
+}
+
+impl Universe for HermiteUniverse {
+    fn advance(&mut self, time: f64) {
+        let direction = time.signum();
+        let mut remaining_time = time.abs();
+
+        while remaining_time > 0.0 {
+            let step_size = self.step_size.min(remaining_time);
+
+            self.step_size = self.step(step_size * direction);
+
+            remaining_time -= step_size;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HermiteUniverse;
+    use crate::body::Body;
+    use crate::test_utilities::{
+        check_angular_momentum, check_binary_orbit, check_center_of_mass, check_energy, check_momentum,
+        check_reversibility, check_trajectory, get_binary_bodies, get_taylor_bodies,
+    };
+    use crate::universe::Universe;
+    use cgmath::{InnerSpace, Vector2};
+
+    fn get_taylor_universe() -> HermiteUniverse {
+        HermiteUniverse::new(&get_taylor_bodies(), 0.001)
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(get_taylor_universe(), 0.1, 10, 1.0e-6);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        check_center_of_mass(get_taylor_universe(), 0.1, 10, 1.0e-23);
+    }
+
+    #[test]
+    fn momentum() {
+        check_momentum(get_taylor_universe(), 0.1, 10, 1.0e-18);
+    }
+
+    #[test]
+    fn angular_momentun() {
+        check_angular_momentum(get_taylor_universe(), 0.1, 10, 1.0e-8);
+    }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(get_taylor_universe(), 0.1, 10, 1.0e-20);
+    }
+
+    #[test]
+    fn reversibility() {
+        // Not time-symmetric, but accurate enough to return along the same trajectory.
+
+        check_reversibility(get_taylor_universe(), 0.1, 10, 1.0e-20);
+    }
+
+    #[test]
+    fn close_encounter() {
+        let mut universe = HermiteUniverse::new(&get_binary_bodies(0.999), 0.001);
+
+        check_binary_orbit(&mut universe, 100, 1.0e-5, 1.0e-10);
+
+        assert!(universe.get_steps() > 100);
+    }
+
+    #[test]
+    fn charges() {
+        // Like charges of equal bodies weaken their attraction as smaller masses would.
+
+        let bodies = get_binary_bodies(0.5);
+        let charged_bodies = bodies.iter().map(|b| b.clone().with_charge(0.5)).collect::<Vec<_>>();
+        let light_bodies = bodies
+            .iter()
+            .map(|b| Body {
+                mass: 0.75,
+                ..b.clone()
+            })
+            .collect::<Vec<_>>();
+        let mut universe = HermiteUniverse::new(&charged_bodies, 0.001);
+        let mut light_universe = HermiteUniverse::new(&light_bodies, 0.001);
+
+        for _ in 0..10 {
+            universe.advance(0.1);
+            light_universe.advance(0.1);
+        }
+
+        for (body, light_body) in universe.get_bodies().iter().zip(light_universe.get_bodies()) {
+            assert!((body.position - light_body.position).magnitude() < 1.0e-12);
+        }
+    }
+
+    #[test]
+    fn start_from_rest() {
+        // Without jerks at the start, the first step still resolves the fall towards the near collision.
+
+        let bodies = [
+            Body::new(1.0, Vector2::new(-0.5, 0.0), Vector2::new(0.0, 0.0)),
+            Body::new(1.0, Vector2::new(0.5, 0.0), Vector2::new(0.0, 0.0)),
+        ];
+
+        check_trajectory(HermiteUniverse::new(&bodies, 0.01), 0.7, 1, 1.0e-10);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::complex::Complex;
+use cgmath::Vector2;
+
+// Computes Σ (-x)ⁿ / (2n + k)! for k = 0, 1 or 3, which are cos(√x), sin(√x) / √x and (√x - sin(√x)) / √x³ for
+// positive x, and their hyperbolic counterparts for negative x.
+fn stumpff(k: i32, x: f64) -> f64 {
+    if x.abs() < 4.0 {
+        let mut term = (1..=k).map(f64::from).product::<f64>().recip();
+        let mut sum = term;
+        let mut n = 0;
+
+        while term.abs() > sum.abs() * f64::EPSILON {
+            n += 1;
+            term *= -x / f64::from((2 * n + k - 1) * (2 * n + k));
+            sum += term;
+        }
+
+        sum
+    } else if x > 0.0 {
+        let root = x.sqrt();
+
+        match k {
+            0 => root.cos(),
+            1 => root.sin() / root,
+            _ => (root - root.sin()) / (x * root),
+        }
+    } else {
+        let root = (-x).sqrt();
+
+        match k {
+            0 => root.cosh(),
+            1 => root.sinh() / root,
+            _ => (root.sinh() - root) / (-x * root),
+        }
+    }
+}
+
+/// Moves a body in the Kepler potential -μ / r of a fixed center at the origin for the given time, which may be
+/// negative, and returns its new position and velocity.
+///
+/// The orbit is solved in Levi-Civita coordinates, where the position z = u² is the square of a complex number u, and
+/// time is stretched by dt = |u|² ds. Every orbit becomes a harmonic oscillation u'' = (h / 2) u in the fictitious time
+/// s with the specific energy h, which is regular at collisions, and is solved exactly for bound, parabolic and
+/// hyperbolic orbits alike.
+pub fn kepler_drift(
+    mu: f64,
+    position: Vector2<f64>,
+    velocity: Vector2<f64>,
+    time: f64,
+) -> (Vector2<f64>, Vector2<f64>) {
+    let z = Complex::from(position);
+    let v = Complex::from(velocity);
+    let r = z.norm();
+
+    if time == 0.0 || r == 0.0 {
+        return (position, velocity);
+    }
+
+    let u = z.sqrt();
+    let u_prime = u.conj() * v * 0.5;
+    let beta = -0.5 * (0.5 * v.norm_sqr() - mu / r);
+
+    // With C = cos(√β s) and S = sin(√β s) / √β, u(s) = u C + u' S, and |u(s)|² integrates to t(s).
+
+    let u_squared = r;
+    let u_prime_squared = u_prime.norm_sqr();
+    let u_u_prime = (u * u_prime.conj()).re;
+
+    let functions = |s: f64| {
+        let x = beta * s * s;
+        let c = stumpff(0, x);
+        let s_function = s * stumpff(1, x);
+
+        let t = u_squared * s * (1.0 + stumpff(1, 4.0 * x)) * 0.5
+            + u_prime_squared * 2.0 * s * s * s * stumpff(3, 4.0 * x)
+            + u_u_prime * s_function * s_function;
+
+        let radius = u_squared * c * c + u_prime_squared * s_function * s_function + 2.0 * u_u_prime * c * s_function;
+
+        (t, radius, c, s_function)
+    };
+
+    // Brackets the fictitious time, which grows with time, and refines it with Newton's method, falling back to
+    // bisection.
+
+    let mut low = 0.0;
+    let mut high = time / r;
+
+    while (functions(high).0 - time) * time.signum() < 0.0 {
+        low = high;
+        high *= 2.0;
+    }
+
+    let mut s = high;
+
+    for _ in 0..100 {
+        let (t, radius, _, _) = functions(s);
+        let error = t - time;
+
+        if error * time.signum() < 0.0 {
+            low = s;
+        } else {
+            high = s;
+        }
+
+        let mut next_s = s - error / radius;
+
+        if (next_s - low) * (next_s - high) > 0.0 {
+            next_s = 0.5 * (low + high);
+        }
+
+        let converged = (next_s - s).abs() <= f64::EPSILON * s.abs();
+
+        s = next_s;
+
+        if converged {
+            break;
+        }
+    }
+
+    let (_, radius, c, s_function) = functions(s);
+    let new_u = u * c + u_prime * s_function;
+    let new_u_prime = u_prime * c - u * (beta * s_function);
+
+    ((new_u * new_u).into(), (new_u * new_u_prime * (2.0 / radius)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{kepler_drift, stumpff};
+    use crate::utilities::cross;
+    use cgmath::{InnerSpace, Vector2};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn check_stumpff() {
+        for &x in &[-30.0f64, -4.5, -3.9, -0.3, 0.0, 0.3, 3.9, 4.5, 30.0] {
+            let root = f64::sqrt(x.abs());
+
+            let (c0, c1, c3) = if x > 0.0 {
+                (root.cos(), root.sin() / root, (root - root.sin()) / root.powi(3))
+            } else if x < 0.0 {
+                (root.cosh(), root.sinh() / root, (root.sinh() - root) / root.powi(3))
+            } else {
+                (1.0, 1.0, 1.0 / 6.0)
+            };
+
+            assert!((stumpff(0, x) - c0).abs() < 1.0e-12 * c0.abs().max(1.0));
+            assert!((stumpff(1, x) - c1).abs() < 1.0e-12 * c1.abs().max(1.0));
+            assert!((stumpff(3, x) - c3).abs() < 1.0e-9 * c3.abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn conservation() {
+        // Bound, nearly parabolic and hyperbolic orbits, forwards and backwards in time.
+
+        for &speed in &[0.3, 1.0, 1.88, 3.0] {
+            for &time in &[0.7, -0.7, 25.0] {
+                let position = Vector2::new(1.0, 0.5);
+                let velocity = Vector2::new(-0.2, speed);
+                let (new_position, new_velocity) = kepler_drift(2.0, position, velocity, time);
+
+                let energy = |p: Vector2<f64>, v: Vector2<f64>| 0.5 * v.magnitude2() - 2.0 / p.magnitude();
+
+                assert!((energy(new_position, new_velocity) - energy(position, velocity)).abs() < 1.0e-12);
+                assert!((cross(new_position, new_velocity) - cross(position, velocity)).abs() < 1.0e-12);
+
+                // Going back in time returns to the start.
+
+                let (old_position, old_velocity) = kepler_drift(2.0, new_position, new_velocity, -time);
+
+                assert!((old_position - position).magnitude() < 1.0e-10 * time.abs().max(1.0));
+                assert!((old_velocity - velocity).magnitude() < 1.0e-10 * time.abs().max(1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn period() {
+        // Orbits return after 2π √(a³ / μ), where a is the semi-major axis.
+
+        let position = Vector2::new(0.6, 0.8);
+        let velocity = Vector2::new(-0.8, 0.6) * 2.0f64.sqrt();
+        let (new_position, new_velocity) = kepler_drift(2.0, position, velocity, 2.0 * PI / 2.0f64.sqrt());
+
+        assert!((new_position - position).magnitude() < 1.0e-12);
+        assert!((new_velocity - velocity).magnitude() < 1.0e-12);
+
+        // Radial fall from rest, which bounces back from the collision.
+
+        let position = Vector2::new(1.0, 0.0);
+        let velocity = Vector2::new(0.0, 0.0);
+        let (new_position, new_velocity) = kepler_drift(2.0, position, velocity, 2.0 * PI / 4.0);
+
+        assert!((new_position - position).magnitude() < 1.0e-12);
+        assert!(new_velocity.magnitude() < 1.0e-6);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::body::Body;
+use crate::external_field::ExternalField;
+use crate::force_law::ForceLaw;
+use crate::forces::{add_external_accelerations, compute_accelerations, compute_test_particle_accelerations};
+use crate::softening::Softening;
+use crate::test_particle::TestParticle;
+use crate::universe::Universe;
+use cgmath::Vector2;
+
+pub struct LeapfrogUniverse {
+    bodies: Vec<Body>,
+    test_particles: Vec<TestParticle>,
+    external_fields: Vec<ExternalField>,
+    time: f64,                                            // State.
+    acceleration_buffer: Vec<Vector2<f64>>,               // Accelerations of the current positions.
+    test_particle_acceleration_buffer: Vec<Vector2<f64>>, // Accelerations of the current positions.
+}
+
+impl LeapfrogUniverse {
+    pub fn new(bodies: &[Body]) -> LeapfrogUniverse {
+        let mut acceleration_buffer = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        compute_accelerations(bodies, &mut acceleration_buffer);
+
+        LeapfrogUniverse {
+            bodies: bodies.to_vec(),
+            test_particles: Vec::new(),
+            external_fields: Vec::new(),
+            time: 0.0,
+            acceleration_buffer,
+            test_particle_acceleration_buffer: Vec::new(),
+        }
+    }
+
+    pub fn with_test_particles(self, test_particles: &[TestParticle]) -> LeapfrogUniverse {
+        let mut universe = LeapfrogUniverse {
+            test_particles: test_particles.to_vec(),
+            test_particle_acceleration_buffer: vec![Vector2 { x: 0.0, y: 0.0 }; test_particles.len()],
+            ..self
+        };
+
+        universe.update_accelerations();
+
+        universe
+    }
+
+    pub fn with_external_fields(self, external_fields: &[ExternalField]) -> LeapfrogUniverse {
+        let mut universe = LeapfrogUniverse {
+            external_fields: external_fields.to_vec(),
+            ..self
+        };
+
+        universe.update_accelerations();
+
+        universe
+    }
+
+    // Computes the accelerations of the current positions of bodies and test particles.
+    fn update_accelerations(&mut self) {
+        compute_accelerations(&self.bodies, &mut self.acceleration_buffer);
+
+        compute_test_particle_accelerations(
+            &self.bodies,
+            ForceLaw::InverseSquare,
+            Softening::None,
+            &self.test_particles,
+            &mut self.test_particle_acceleration_buffer,
+        );
+
+        add_external_accelerations(
+            &self.external_fields,
+            self.time,
+            self.bodies.iter().map(|b| b.position),
+            &mut self.acceleration_buffer,
+        );
+
+        add_external_accelerations(
+            &self.external_fields,
+            self.time,
+            self.test_particles.iter().map(|p| p.position),
+            &mut self.test_particle_acceleration_buffer,
+        );
+    }
+}
+
+impl Universe for LeapfrogUniverse {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        // Kick and drift.
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+            body.position += body.velocity * time;
+        }
+
+        for (test_particle, acceleration) in self
+            .test_particles
+            .iter_mut()
+            .zip(&self.test_particle_acceleration_buffer)
+        {
+            test_particle.velocity += acceleration * half_time;
+            test_particle.position += test_particle.velocity * time;
+        }
+
+        // Kick again using the accelerations of the new positions, which are kept for the next step.
+
+        self.time += time;
+        self.update_accelerations();
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+
+        for (test_particle, acceleration) in self
+            .test_particles
+            .iter_mut()
+            .zip(&self.test_particle_acceleration_buffer)
+        {
+            test_particle.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+
+    fn get_test_particles(&self) -> &[TestParticle] {
+        &self.test_particles
+    }
+
+    fn get_external_fields(&self) -> &[ExternalField] {
+        &self.external_fields
+    }
+
+    fn get_time(&self) -> f64 {
+        self.time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LeapfrogUniverse;
+    use crate::body::Body;
+    use crate::external_field::ExternalField;
+    use crate::test_particle::TestParticle;
+    use crate::test_utilities::{
+        check_angular_momentum, check_center_of_mass, check_energy, check_momentum, check_reversibility,
+        check_trajectory, get_binary_bodies, get_taylor_bodies,
+    };
+    use crate::universe::Universe;
+    use cgmath::{InnerSpace, Vector2};
+    use std::f64::consts::PI;
+
+    fn get_taylor_universe() -> LeapfrogUniverse {
+        LeapfrogUniverse::new(&get_taylor_bodies())
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(get_taylor_universe(), 0.001, 1000, 1.0e-4);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        check_center_of_mass(get_taylor_universe(), 0.001, 1000, 1.0e-23);
+    }
+
+    #[test]
+    fn momentum() {
+        check_momentum(get_taylor_universe(), 0.001, 1000, 1.0e-18);
+    }
+
+    #[test]
+    fn angular_momentun() {
+        check_angular_momentum(get_taylor_universe(), 0.001, 1000, 1.0e-8);
+    }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(get_taylor_universe(), 0.001, 1000, 1.0e-15);
+    }
+
+    #[test]
+    fn reversibility() {
+        check_reversibility(get_taylor_universe(), 0.001, 1000, 1.0e-24);
+    }
+
+    #[test]
+    fn test_particles() {
+        // Test particles on circular orbits around a single body stay on their orbits, and leave the body at rest.
+
+        let bodies = [Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0))];
+
+        let test_particles = [0.5, 1.0, 2.0]
+            .iter()
+            .map(|&radius: &f64| TestParticle::new(Vector2::new(radius, 0.0), Vector2::new(0.0, radius.sqrt().recip())))
+            .collect::<Vec<_>>();
+
+        let mut universe = LeapfrogUniverse::new(&bodies).with_test_particles(&test_particles);
+
+        // One orbit of the innermost test particle.
+
+        for _ in 0..10000 {
+            universe.advance(PI * 0.5f64.powf(1.5) * 2.0 / 10000.0);
+        }
+
+        for (test_particle, initial_test_particle) in universe.get_test_particles().iter().zip(&test_particles) {
+            assert!((test_particle.position.magnitude() - initial_test_particle.position.x).abs() < 1.0e-6);
+        }
+
+        assert!((universe.get_test_particles()[0].position - test_particles[0].position).magnitude() < 1.0e-6);
+        assert_eq!(universe.get_bodies()[0].position, bodies[0].position);
+    }
+
+    #[test]
+    fn external_fields() {
+        // The Jacobi integral is conserved in a rotating bar, and test particles feel the bar.
+
+        let pattern_speed = 0.5;
+
+        let external_fields = [ExternalField::RotatingBar {
+            mass: 10.0,
+            length: 2.0,
+            pattern_speed,
+        }];
+
+        let test_particles = [TestParticle::new(Vector2::new(0.0, 3.0), Vector2::new(-2.0, 0.0))];
+
+        let mut universe = LeapfrogUniverse::new(&get_binary_bodies(0.5))
+            .with_test_particles(&test_particles)
+            .with_external_fields(&external_fields);
+
+        let jacobi_integral =
+            |universe: &LeapfrogUniverse| universe.get_energy() - pattern_speed * universe.get_angular_momentum();
+
+        let j0 = jacobi_integral(&universe);
+
+        for _ in 0..10000 {
+            universe.advance(0.0001);
+        }
+
+        assert!((universe.get_time() - 1.0).abs() < 1.0e-12);
+        assert!((jacobi_integral(&universe) - j0).abs() < 1.0e-6 * j0.abs());
+        assert!(universe.get_test_particles()[0].velocity.y < -1.0);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::body3::Body3;
+use crate::forces::compute_accelerations3;
+use crate::universe3::Universe3;
+use cgmath::Vector3;
+
+/// `LeapfrogUniverse` in 3D space.
+pub struct LeapfrogUniverse3 {
+    bodies: Vec<Body3>,
+    acceleration_buffer: Vec<Vector3<f64>>, // Accelerations of the current positions.
+}
+
+impl LeapfrogUniverse3 {
+    pub fn new(bodies: &[Body3]) -> LeapfrogUniverse3 {
+        let mut acceleration_buffer = vec![Vector3 { x: 0.0, y: 0.0, z: 0.0 }; bodies.len()];
+
+        compute_accelerations3(bodies, &mut acceleration_buffer);
+
+        LeapfrogUniverse3 {
+            bodies: bodies.to_vec(),
+            acceleration_buffer,
+        }
+    }
+}
+
+impl Universe3 for LeapfrogUniverse3 {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+            body.position += body.velocity * time;
+        }
+
+        compute_accelerations3(&self.bodies, &mut self.acceleration_buffer);
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body3] {
+        &self.bodies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LeapfrogUniverse3;
+    use crate::body3::Body3;
+    use crate::leapfrog_universe::LeapfrogUniverse;
+    use crate::test_utilities::get_taylor_bodies;
+    use crate::universe::Universe;
+    use crate::universe3::Universe3;
+    use cgmath::{InnerSpace, Vector3};
+
+    fn get_spatial_bodies() -> Vec<Body3> {
+        vec![
+            Body3::new(2.0, Vector3::new(3.0, 5.0, -7.0), Vector3::new(7.0, 11.0, 2.0)),
+            Body3::new(13.0, Vector3::new(19.0, 19.0, 3.0), Vector3::new(23.0, 29.0, -5.0)),
+            Body3::new(31.0, Vector3::new(37.0, 41.0, 11.0), Vector3::new(43.0, 47.0, 13.0)),
+            Body3::new(53.0, Vector3::new(59.0, 61.0, -17.0), Vector3::new(67.0, 71.0, 0.0)),
+        ]
+    }
+
+    #[test]
+    fn planar() {
+        // Bodies in a plane stay in it, and move as in 2D space.
+
+        let bodies = get_taylor_bodies();
+        let mut universe = LeapfrogUniverse::new(&bodies);
+        let mut universe3 = LeapfrogUniverse3::new(&bodies.iter().map(Body3::from).collect::<Vec<_>>());
+
+        assert!((universe3.get_energy() - universe.get_energy()).abs() < 1.0e-9);
+
+        for _ in 0..1000 {
+            universe.advance(0.001);
+            universe3.advance(0.001);
+        }
+
+        for (body, body3) in universe.get_bodies().iter().zip(universe3.get_bodies()) {
+            assert_eq!(body3.position, body.position.extend(0.0));
+            assert_eq!(body3.velocity, body.velocity.extend(0.0));
+        }
+
+        let angular_momentum = universe3.get_angular_momentum();
+
+        assert_eq!(angular_momentum.truncate(), [0.0, 0.0].into());
+        assert!((angular_momentum.z - universe.get_angular_momentum()).abs() < 1.0e-8);
+    }
+
+    #[test]
+    fn conservation() {
+        let mut universe = LeapfrogUniverse3::new(&get_spatial_bodies());
+        let energy = universe.get_energy();
+        let momentum = universe.get_momentum();
+        let angular_momentum = universe.get_angular_momentum();
+
+        for _ in 0..1000 {
+            universe.advance(0.001);
+        }
+
+        assert!((universe.get_energy() - energy).abs() < 1.0e-4 * energy.abs());
+        assert!((universe.get_momentum() - momentum).magnitude() < 1.0e-10);
+        assert!((universe.get_angular_momentum() - angular_momentum).magnitude() < 1.0e-8);
+    }
+}
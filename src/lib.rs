@@ -1,3 +1,4 @@
+use cgmath::{Vector2, Vector3};
 use rand::random;
 use std::cell::RefCell;
 use std::panic;
@@ -7,26 +8,89 @@ use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{CanvasRenderingContext2d, Document, HtmlCanvasElement, KeyboardEvent, Window};
 
 mod basic_renderer;
+mod complex;
 mod configuration;
 mod distributions;
+mod ewald;
+mod fast_multipole;
+mod fft;
+mod forces;
+mod kepler;
+mod particle_mesh;
+mod quadtree;
 mod url_configuration;
 mod utilities;
 
+#[cfg(test)]
+mod test_utilities;
+
+pub mod barnes_hut_universe;
 pub mod basic_scheduler;
 pub mod basic_universe;
 pub mod body;
+pub mod body3;
+pub mod bulirsch_stoer_universe;
+pub mod camera;
+pub mod collision;
+pub mod composition_universe;
+pub mod dormand_prince_universe;
+pub mod drag;
+pub mod external_field;
+pub mod fast_multipole_universe;
+pub mod force_law;
+pub mod gauss_radau_universe;
+pub mod hermite_universe;
+pub mod leapfrog_universe;
+pub mod leapfrog_universe3;
+pub mod particle_mesh_universe;
+pub mod periodic_universe;
+pub mod precession;
+pub mod projected_universe;
+pub mod projection;
+pub mod regularized_universe;
 pub mod renderer;
 pub mod scheduler;
+pub mod softening;
+pub mod spherical_universe;
+pub mod test_particle;
 pub mod universe;
+pub mod universe3;
+pub mod wisdom_holman_universe;
 
+use crate::barnes_hut_universe::BarnesHutUniverse;
 use crate::basic_renderer::BasicRenderer;
 use crate::basic_scheduler::BasicScheduler;
 use crate::basic_universe::BasicUniverse;
-use crate::configuration::{random_configuration, Configuration};
+use crate::body3::Body3;
+use crate::bulirsch_stoer_universe::BulirschStoerUniverse;
+use crate::camera::Camera;
+use crate::collision::Collision;
+use crate::composition_universe::CompositionUniverse;
+use crate::configuration::{galaxy_configuration, random_configuration, ring_configuration, Configuration};
+use crate::dormand_prince_universe::DormandPrinceUniverse;
+use crate::drag::Drag;
+use crate::external_field::ExternalField;
+use crate::fast_multipole_universe::FastMultipoleUniverse;
+use crate::force_law::ForceLaw;
+use crate::gauss_radau_universe::GaussRadauUniverse;
+use crate::hermite_universe::HermiteUniverse;
+use crate::leapfrog_universe::LeapfrogUniverse;
+use crate::leapfrog_universe3::LeapfrogUniverse3;
+use crate::particle_mesh_universe::{BoundaryCondition, ParticleMeshUniverse};
+use crate::periodic_universe::PeriodicUniverse;
+use crate::projected_universe::ProjectedUniverse;
+use crate::projection::StereographicProjection;
+use crate::regularized_universe::RegularizedUniverse;
 use crate::renderer::Renderer;
 use crate::scheduler::Scheduler;
+use crate::softening::Softening;
+use crate::spherical_universe::SphericalUniverse;
 use crate::universe::Universe;
-use crate::url_configuration::{random_url_configuration, SchedulerType, UrlConfiguration};
+use crate::url_configuration::{
+    random_url_configuration, CollisionType, DragType, ExternalFieldType, ForceLawType, SchedulerType, UniverseType,
+    UrlConfiguration,
+};
+use crate::wisdom_holman_universe::WisdomHolmanUniverse;
 
 fn bind_keys(window: &Window, url_configuration: UrlConfiguration) {
     let closure = Closure::wrap(Box::new({
@@ -44,7 +108,7 @@ fn bind_keys(window: &Window, url_configuration: UrlConfiguration) {
                     .unwrap();
             }
         }
-    }) as Box<Fn(_)>);
+    }) as Box<dyn Fn(_)>);
 
     window
         .add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())
@@ -53,29 +117,8 @@ fn bind_keys(window: &Window, url_configuration: UrlConfiguration) {
     closure.forget();
 }
 
-fn build_universe_and_renderer_and_scheduler(
-    configuration: Configuration,
-    canvas_context: CanvasRenderingContext2d,
-    width: f64,
-    height: f64,
-) -> (impl Universe, impl Renderer, impl Scheduler) {
-    let universe = BasicUniverse::new(&configuration.bodies.iter().map(|b| b.body.clone()).collect::<Vec<_>>());
-
-    let renderer = BasicRenderer::new(
-        canvas_context,
-        width,
-        height,
-        configuration.bodies.iter().map(|b| b.color.clone()).collect(),
-        configuration.bodies.iter().map(|b| b.trail_width).collect(),
-    );
-
-    let scheduler = BasicScheduler::new(configuration.sample_frequency, &universe);
-
-    (universe, renderer, scheduler)
-}
-
 fn run_animation_frame_loop<F: FnMut(f64) + 'static>(window: &Window, mut f: F) {
-    fn do_request_animation_frame(window: &Window, f: &Closure<FnMut(f64)>) {
+    fn do_request_animation_frame(window: &Window, f: &Closure<dyn FnMut(f64)>) {
         window.request_animation_frame(f.as_ref().unchecked_ref()).unwrap();
     }
 
@@ -94,7 +137,7 @@ fn run_animation_frame_loop<F: FnMut(f64) + 'static>(window: &Window, mut f: F)
         }
     }) as _));
 
-    do_request_animation_frame(&window, closure_rc_1.borrow().as_ref().unwrap());
+    do_request_animation_frame(window, closure_rc_1.borrow().as_ref().unwrap());
 }
 
 fn run_and_render_universe<U: Universe, R: Renderer, S: Scheduler>(
@@ -103,12 +146,131 @@ fn run_and_render_universe<U: Universe, R: Renderer, S: Scheduler>(
     mut renderer: R,
     mut scheduler: S,
 ) {
-    run_animation_frame_loop(&window, move |timestamp| {
+    run_animation_frame_loop(window, move |timestamp| {
         scheduler.advance(timestamp, &mut universe, &mut renderer)
     });
 }
 
+fn run_universe<U: Universe, R: Renderer>(
+    window: &Window,
+    universe: U,
+    renderer: R,
+    sample_frequency: f64,
+    backward: bool,
+) {
+    let mut scheduler = BasicScheduler::new(sample_frequency, &universe);
+
+    if backward {
+        scheduler = scheduler.with_backward_playback();
+    }
+
+    run_and_render_universe(window, universe, renderer, scheduler);
+}
+
 fn main(window: Window, document: Document, url_configuration: UrlConfiguration, configuration: Configuration) {
+    let universe_type = url_configuration.universe.clone();
+
+    let softening = if url_configuration.softening > 0.0 {
+        Softening::Plummer(url_configuration.softening)
+    } else {
+        Softening::None
+    };
+
+    // Default parameters are chosen so that the forces are comparable to gravity at the scale of the configurations.
+
+    let force_parameter = |default: f64| {
+        if url_configuration.force_parameter > 0.0 {
+            url_configuration.force_parameter
+        } else {
+            default
+        }
+    };
+
+    let force_law = match url_configuration.force_law {
+        ForceLawType::InverseSquare => ForceLaw::InverseSquare,
+        ForceLawType::InversePower => ForceLaw::InversePower(force_parameter(3.0)),
+        ForceLawType::Yukawa => ForceLaw::Yukawa(force_parameter(256.0)),
+        ForceLawType::LennardJones => {
+            let length = force_parameter(32.0);
+
+            ForceLaw::LennardJones {
+                depth: length.recip(),
+                length,
+            }
+        }
+        ForceLawType::Harmonic => ForceLaw::Harmonic(force_parameter(2.0f64.powi(-24))),
+    };
+
+    let drag_parameter = |default: f64| {
+        if url_configuration.drag_parameter > 0.0 {
+            url_configuration.drag_parameter
+        } else {
+            default
+        }
+    };
+
+    let drags = match url_configuration.drag {
+        DragType::None => vec![],
+        DragType::Linear => vec![Drag::Linear(drag_parameter(2.0f64.powi(-3)))],
+        DragType::Quadratic => vec![Drag::Quadratic(drag_parameter(2.0f64.powi(-10)))],
+        DragType::DynamicalFriction => vec![Drag::DynamicalFriction {
+            density: drag_parameter(2.0f64.powi(-9)),
+            velocity_dispersion: 2.0f64.powi(5),
+            coulomb_logarithm: 3.0,
+        }],
+    };
+
+    // Universes without Coulomb forces get uncharged bodies, which would move as if they were uncharged anyway.
+
+    let charge_ratio = match universe_type {
+        UniverseType::Basic
+        | UniverseType::Leapfrog
+        | UniverseType::ForestRuth
+        | UniverseType::Yoshida
+        | UniverseType::DormandPrince
+        | UniverseType::Hermite
+        | UniverseType::BulirschStoer
+        | UniverseType::GaussRadau
+        | UniverseType::Regularized
+        | UniverseType::WisdomHolman => url_configuration.charge,
+        _ => 0.0,
+    };
+
+    let speed_of_light = url_configuration.speed_of_light;
+    let radiation_reaction = url_configuration.radiation_reaction;
+
+    // Fields are about as strong as the bodies of the random configuration.
+
+    let external_fields = match url_configuration.external_field {
+        ExternalFieldType::None => vec![],
+        ExternalFieldType::PointMass => vec![ExternalField::PointMass(2.0f64.powi(22))],
+        ExternalFieldType::LogarithmicHalo => vec![ExternalField::LogarithmicHalo {
+            velocity: 2.0f64.powi(7),
+            core_radius: 2.0f64.powi(6),
+        }],
+        ExternalFieldType::MiyamotoNagai => vec![ExternalField::MiyamotoNagai {
+            mass: 2.0f64.powi(22),
+            length: 2.0f64.powi(6),
+            height: 2.0f64.powi(4),
+        }],
+        ExternalFieldType::Uniform => vec![ExternalField::Uniform(Vector2::new(0.0, 2.0f64.powi(4)))],
+        ExternalFieldType::RotatingBar => vec![ExternalField::RotatingBar {
+            mass: 2.0f64.powi(22),
+            length: 2.0f64.powi(8),
+            pattern_speed: 0.25,
+        }],
+    };
+
+    let collision = match url_configuration.collision {
+        CollisionType::None => Collision::None,
+        CollisionType::Merge => Collision::Merge,
+        CollisionType::Bounce => Collision::Bounce {
+            restitution: url_configuration.restitution,
+        },
+    };
+
+    let backward = url_configuration.backward;
+
     bind_keys(&window, url_configuration);
 
     let (context, canvas_width, canvas_height) = {
@@ -145,10 +307,187 @@ fn main(window: Window, document: Document, url_configuration: UrlConfiguration,
         (context, canvas_width, canvas_height)
     };
 
-    let (universe, renderer, scheduler) =
-        build_universe_and_renderer_and_scheduler(configuration, context, canvas_width, canvas_height);
+    let renderer = BasicRenderer::new(
+        context,
+        canvas_width,
+        canvas_height,
+        configuration.bodies.iter().map(|b| b.color.clone()).collect(),
+        configuration.bodies.iter().map(|b| b.trail_width).collect(),
+    );
 
-    run_and_render_universe(&window, universe, renderer, scheduler);
+    let test_particles = configuration.test_particles;
+
+    let bodies = configuration
+        .bodies
+        .into_iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let charge = b.body.mass * charge_ratio;
+
+            b.body.with_charge(if i % 2 == 0 { charge } else { -charge })
+        })
+        .collect::<Vec<_>>();
+    let sample_frequency = configuration.sample_frequency;
+
+    match universe_type {
+        UniverseType::Basic => {
+            let mut universe = BasicUniverse::new(&bodies)
+                .with_collision(collision)
+                .with_softening(softening)
+                .with_force_law(force_law)
+                .with_test_particles(&test_particles)
+                .with_external_fields(&external_fields)
+                .with_drags(&drags);
+
+            if speed_of_light > 0.0 {
+                universe = universe.with_speed_of_light(speed_of_light);
+            }
+
+            if radiation_reaction > 0.0 {
+                universe = universe.with_radiation_reaction(radiation_reaction);
+            }
+
+            run_universe(&window, universe, renderer, sample_frequency, backward)
+        }
+        UniverseType::Leapfrog => run_universe(
+            &window,
+            LeapfrogUniverse::new(&bodies)
+                .with_test_particles(&test_particles)
+                .with_external_fields(&external_fields),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::ForestRuth => run_universe(
+            &window,
+            CompositionUniverse::forest_ruth(&bodies),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::Yoshida => run_universe(
+            &window,
+            CompositionUniverse::yoshida(&bodies),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::DormandPrince => run_universe(
+            &window,
+            DormandPrinceUniverse::new(&bodies, 1.0e-9, 1.0e-9),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::Hermite => run_universe(
+            &window,
+            HermiteUniverse::new(&bodies, 0.01),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::BulirschStoer => run_universe(
+            &window,
+            BulirschStoerUniverse::new(&bodies, 1.0e-12, 1.0e-12),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::BarnesHut => run_universe(
+            &window,
+            BarnesHutUniverse::new(&bodies, 0.5),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::FastMultipole => run_universe(
+            &window,
+            FastMultipoleUniverse::new(&bodies, 8),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::ParticleMesh => run_universe(
+            &window,
+            ParticleMeshUniverse::new(&bodies, 256, 2048.0, BoundaryCondition::Isolated),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::Leapfrog3 => {
+            // The plane of the bodies is viewed from above at an angle.
+
+            let camera = Camera::new(
+                Vector3::new(0.0, 600.0, -800.0),
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(0.0, -1.0, 0.0),
+                1000.0,
+                1.0,
+            );
+
+            let universe = LeapfrogUniverse3::new(&bodies.iter().map(Body3::from).collect::<Vec<_>>());
+
+            run_universe(
+                &window,
+                ProjectedUniverse::new(universe, camera),
+                renderer,
+                sample_frequency,
+                backward,
+            )
+        }
+        UniverseType::Spherical => {
+            // The plane is wrapped around a sphere, which is drawn so that the bodies start where they would in the
+            // plane.
+
+            let radius = 512.0;
+            let projection = StereographicProjection::new(radius);
+
+            let bodies = bodies
+                .iter()
+                .map(|b| {
+                    let (position, velocity) = projection.unproject(b.position, b.velocity);
+
+                    Body3::new(b.mass, position, velocity)
+                })
+                .collect::<Vec<_>>();
+
+            run_universe(
+                &window,
+                ProjectedUniverse::new(SphericalUniverse::new(&bodies, radius), projection),
+                renderer,
+                sample_frequency,
+                backward,
+            )
+        }
+        UniverseType::Periodic => run_universe(
+            &window,
+            PeriodicUniverse::new(&bodies, 1024.0),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::Regularized => run_universe(
+            &window,
+            RegularizedUniverse::new(&bodies, 64.0),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::WisdomHolman => run_universe(
+            &window,
+            WisdomHolmanUniverse::new(&bodies),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+        UniverseType::GaussRadau => run_universe(
+            &window,
+            GaussRadauUniverse::new(&bodies, 1.0e-9),
+            renderer,
+            sample_frequency,
+            backward,
+        ),
+    }
 }
 
 fn load_url_configuration(window: &Window) -> UrlConfiguration {
@@ -181,8 +520,31 @@ fn load_url_configuration(window: &Window) -> UrlConfiguration {
 }
 
 fn generate_configuration(url_configuration: UrlConfiguration) -> Configuration {
+    // Higher order integrators stay accurate with larger steps, which compensates for their more expensive steps.
+
+    let sample_frequency = match url_configuration.universe {
+        UniverseType::Basic => 1_000_000.0,
+        UniverseType::Leapfrog => 100_000.0,
+        UniverseType::ForestRuth => 50_000.0,
+        UniverseType::Yoshida => 20_000.0,
+        UniverseType::DormandPrince => 10_000.0,
+        UniverseType::Hermite => 10_000.0,
+        UniverseType::BulirschStoer => 1_000.0,
+        UniverseType::BarnesHut => 50.0,
+        UniverseType::FastMultipole => 50.0,
+        UniverseType::ParticleMesh => 50.0,
+        UniverseType::Leapfrog3 => 100_000.0,
+        UniverseType::Spherical => 100_000.0,
+        UniverseType::Periodic => 1_000.0,
+        UniverseType::Regularized => 10_000.0,
+        UniverseType::WisdomHolman => 10_000.0,
+        UniverseType::GaussRadau => 1_000.0,
+    };
+
     match url_configuration.scheduler {
-        SchedulerType::Basic => random_configuration(url_configuration.id),
+        SchedulerType::Basic => random_configuration(url_configuration.id, sample_frequency),
+        SchedulerType::Galaxy => galaxy_configuration(url_configuration.id, sample_frequency),
+        SchedulerType::Ring => ring_configuration(url_configuration.id, sample_frequency),
     }
 }
 
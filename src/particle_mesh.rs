@@ -0,0 +1,210 @@
+use crate::body::Body;
+use crate::complex::Complex;
+use crate::fft::fft_2d;
+use cgmath::Vector2;
+use std::f64::consts::{PI, SQRT_2};
+
+#[derive(Clone, Copy)]
+pub enum BoundaryCondition {
+    /// The domain repeats infinitely in both directions, and bodies leaving it on one side enter it on the other side.
+    Periodic,
+    /// The domain is surrounded by empty space.
+    Isolated,
+}
+
+/// Particle-mesh gravity solver, which deposits masses on a square grid centered at the origin with cloud-in-cell
+/// weights, computes the potential by FFT convolution, and interpolates its gradient back with the same weights.
+pub struct ParticleMesh {
+    grid_size: usize,
+    fft_size: usize, // Doubled for isolated boundary conditions to avoid wrap-around.
+    cell_size: f64,
+    extent: f64,
+    boundary_condition: BoundaryCondition,
+    green: Vec<Complex>,         // Pre-calculated, Fourier transform of the Green's function.
+    potential: Vec<Complex>,     // Buffer, masses before the convolution.
+    column_buffer: Vec<Complex>, // Buffer.
+    weights: Vec<(usize, f64)>,  // Buffer, cells and weights of a body.
+}
+
+impl ParticleMesh {
+    pub fn new(grid_size: usize, extent: f64, boundary_condition: BoundaryCondition) -> ParticleMesh {
+        assert!(grid_size.is_power_of_two());
+
+        let fft_size = match boundary_condition {
+            BoundaryCondition::Periodic => grid_size,
+            BoundaryCondition::Isolated => grid_size * 2,
+        };
+
+        let cell_size = extent / grid_size as f64;
+        let mut column_buffer = Vec::with_capacity(fft_size);
+
+        // Signed offset of a grid index on the periodic FFT grid.
+        let offset = |i: usize| {
+            if i <= fft_size / 2 {
+                i as f64
+            } else {
+                i as f64 - fft_size as f64
+            }
+        };
+
+        let green = match boundary_condition {
+            BoundaryCondition::Periodic => {
+                // The Fourier transform of -1 / r in the plane is -2π / k. The mean density has no force, so its
+                // component is dropped.
+
+                let wave_number = 2.0 * PI / extent;
+
+                (0..fft_size * fft_size)
+                    .map(|i| {
+                        let k = offset(i % fft_size).hypot(offset(i / fft_size)) * wave_number;
+
+                        if k == 0.0 {
+                            Complex::ZERO
+                        } else {
+                            Complex::new(-2.0 * PI / (k * extent * extent), 0.0)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }
+            BoundaryCondition::Isolated => {
+                // -1 / r sampled on the grid, with the mean potential of a uniform cell over itself at the center.
+
+                let mut green = (0..fft_size * fft_size)
+                    .map(|i| {
+                        let r = offset(i % fft_size).hypot(offset(i / fft_size)) * cell_size;
+
+                        let value = if r == 0.0 {
+                            -4.0 * (1.0 + SQRT_2).ln() / cell_size
+                        } else {
+                            -1.0 / r
+                        };
+
+                        Complex::new(value / (fft_size * fft_size) as f64, 0.0)
+                    })
+                    .collect::<Vec<_>>();
+
+                fft_2d(&mut green, fft_size, false, &mut column_buffer);
+
+                green
+            }
+        };
+
+        ParticleMesh {
+            grid_size,
+            fft_size,
+            cell_size,
+            extent,
+            boundary_condition,
+            green,
+            potential: vec![Complex::ZERO; fft_size * fft_size],
+            column_buffer,
+            weights: Vec::with_capacity(4),
+        }
+    }
+
+    /// Side of the square the domain repeats with for periodic boundary conditions.
+    pub fn get_period(&self) -> Option<f64> {
+        match self.boundary_condition {
+            BoundaryCondition::Periodic => Some(self.extent),
+            BoundaryCondition::Isolated => None,
+        }
+    }
+
+    /// Moves a position outside the domain back into it for periodic boundary conditions.
+    pub fn wrap(&self, position: Vector2<f64>) -> Vector2<f64> {
+        match self.boundary_condition {
+            BoundaryCondition::Periodic => {
+                let half_extent = self.extent * 0.5;
+
+                Vector2::new(
+                    (position.x + half_extent).rem_euclid(self.extent) - half_extent,
+                    (position.y + half_extent).rem_euclid(self.extent) - half_extent,
+                )
+            }
+            BoundaryCondition::Isolated => position,
+        }
+    }
+
+    // Computes the cloud-in-cell weights of the four cells around the position. Cells outside an isolated grid are
+    // left out.
+    fn compute_weights(&mut self, position: Vector2<f64>) {
+        let n = self.grid_size as isize;
+        let u = (position.x + self.extent * 0.5) / self.cell_size - 0.5;
+        let v = (position.y + self.extent * 0.5) / self.cell_size - 0.5;
+        let (x, y) = (u.floor(), v.floor());
+        let (fx, fy) = (u - x, v - y);
+
+        self.weights.clear();
+
+        for &(dx, dy, weight) in &[
+            (0, 0, (1.0 - fx) * (1.0 - fy)),
+            (1, 0, fx * (1.0 - fy)),
+            (0, 1, (1.0 - fx) * fy),
+            (1, 1, fx * fy),
+        ] {
+            let (cell_x, cell_y) = (x as isize + dx, y as isize + dy);
+
+            let cell = match self.boundary_condition {
+                BoundaryCondition::Periodic => Some((cell_x.rem_euclid(n), cell_y.rem_euclid(n))),
+                BoundaryCondition::Isolated => {
+                    if (0..n).contains(&cell_x) && (0..n).contains(&cell_y) {
+                        Some((cell_x, cell_y))
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            if let Some((cell_x, cell_y)) = cell {
+                self.weights
+                    .push((cell_y as usize * self.fft_size + cell_x as usize, weight));
+            }
+        }
+    }
+
+    /// Computes the accelerations of all bodies. Mass outside an isolated grid is ignored, and feels no force.
+    pub fn compute_accelerations(&mut self, bodies: &[Body], accelerations: &mut [Vector2<f64>]) {
+        let m = self.fft_size;
+
+        for value in &mut self.potential {
+            *value = Complex::ZERO;
+        }
+
+        for body in bodies {
+            self.compute_weights(body.position);
+
+            for &(cell, weight) in &self.weights {
+                self.potential[cell].re += body.mass * weight;
+            }
+        }
+
+        fft_2d(&mut self.potential, m, false, &mut self.column_buffer);
+
+        for (value, green) in self.potential.iter_mut().zip(&self.green) {
+            *value = *value * *green;
+        }
+
+        fft_2d(&mut self.potential, m, true, &mut self.column_buffer);
+
+        // Central differences of the potential, where the neighbors of the cells on the edges of an isolated grid are
+        // still in the padded grid.
+
+        let scale = -0.5 / self.cell_size;
+
+        for (body, acceleration) in bodies.iter().zip(accelerations.iter_mut()) {
+            self.compute_weights(body.position);
+
+            *acceleration = Vector2::new(0.0, 0.0);
+
+            for &(cell, weight) in &self.weights {
+                let (x, y) = (cell % m, cell / m);
+                let potential = |x: usize, y: usize| self.potential[(y % m) * m + x % m].re;
+
+                *acceleration += Vector2::new(
+                    potential(x + 1, y) - potential(x + m - 1, y),
+                    potential(x, y + 1) - potential(x, y + m - 1),
+                ) * (weight * scale);
+            }
+        }
+    }
+}
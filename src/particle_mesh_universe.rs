@@ -0,0 +1,159 @@
+use crate::body::Body;
+use crate::particle_mesh::ParticleMesh;
+use crate::universe::Universe;
+use cgmath::Vector2;
+
+pub use crate::particle_mesh::BoundaryCondition;
+
+/// Leapfrog universe with accelerations computed on a grid in O(N + G log G) time, where G is the number of cells.
+/// Forces are smoothed over about a cell, which suits collisionless systems.
+pub struct ParticleMeshUniverse {
+    bodies: Vec<Body>,
+    acceleration_buffer: Vec<Vector2<f64>>, // Accelerations of the current positions.
+    mesh: ParticleMesh,                     // Buffer.
+}
+
+impl ParticleMeshUniverse {
+    /// The grid has `grid_size` × `grid_size` cells, which must be a power of two, and covers a square centered at the
+    /// origin with side `extent`.
+    pub fn new(
+        bodies: &[Body],
+        grid_size: usize,
+        extent: f64,
+        boundary_condition: BoundaryCondition,
+    ) -> ParticleMeshUniverse {
+        let mut mesh = ParticleMesh::new(grid_size, extent, boundary_condition);
+        let mut acceleration_buffer = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+        let mut bodies = bodies.to_vec();
+
+        for body in &mut bodies {
+            body.position = mesh.wrap(body.position);
+        }
+
+        mesh.compute_accelerations(&bodies, &mut acceleration_buffer);
+
+        ParticleMeshUniverse {
+            bodies,
+            acceleration_buffer,
+            mesh,
+        }
+    }
+}
+
+impl Universe for ParticleMeshUniverse {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+            body.position = self.mesh.wrap(body.position + body.velocity * time);
+        }
+
+        self.mesh
+            .compute_accelerations(&self.bodies, &mut self.acceleration_buffer);
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn get_period(&self) -> Option<f64> {
+        self.mesh.get_period()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundaryCondition, ParticleMeshUniverse};
+    use crate::body::Body;
+    use crate::ewald::Ewald;
+    use crate::particle_mesh::ParticleMesh;
+    use crate::test_utilities::get_random_bodies;
+    use crate::universe::Universe;
+    use cgmath::{InnerSpace, Vector2};
+
+    #[test]
+    fn isolated_accelerations() {
+        // Far apart compared with the cell size, the forces approach point mass forces.
+
+        let bodies = [
+            Body::new(3.0, Vector2::new(-10.3, 1.7), Vector2::new(0.0, 0.0)),
+            Body::new(1.0, Vector2::new(14.1, -5.2), Vector2::new(0.0, 0.0)),
+        ];
+
+        let mut accelerations = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        ParticleMesh::new(64, 64.0, BoundaryCondition::Isolated).compute_accelerations(&bodies, &mut accelerations);
+
+        let distance = bodies[1].position - bodies[0].position;
+        let expected_acceleration = distance / distance.magnitude().powi(3);
+
+        assert!((accelerations[0] - expected_acceleration * bodies[1].mass).magnitude() < 1.0e-2 * bodies[1].mass);
+        assert!((accelerations[1] + expected_acceleration * bodies[0].mass).magnitude() < 1.0e-2 * bodies[0].mass);
+    }
+
+    #[test]
+    fn periodic_accelerations() {
+        // A uniform row of bodies along every cell of a periodic grid has no net force.
+
+        let bodies = (0..32)
+            .map(|i| Body::new(1.0, Vector2::new(i as f64 - 15.5, 3.5), Vector2::new(0.0, 0.0)))
+            .collect::<Vec<_>>();
+
+        let mut accelerations = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        ParticleMesh::new(32, 32.0, BoundaryCondition::Periodic).compute_accelerations(&bodies, &mut accelerations);
+
+        for acceleration in accelerations {
+            assert!(acceleration.magnitude() < 1.0e-12);
+        }
+
+        // Far apart compared with the cell size, the forces approach those of Ewald summation, which include all
+        // periodic images against the same uniform background.
+
+        let bodies = [
+            Body::new(3.0, Vector2::new(-10.3, 1.7), Vector2::new(0.0, 0.0)),
+            Body::new(1.0, Vector2::new(4.1, -9.2), Vector2::new(0.0, 0.0)),
+        ];
+
+        let mut accelerations = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        ParticleMesh::new(64, 32.0, BoundaryCondition::Periodic).compute_accelerations(&bodies, &mut accelerations);
+
+        let ewald = Ewald::new(32.0, 3.0 / 32.0);
+        let expected_acceleration = -ewald.gradient(bodies[1].position - bodies[0].position);
+
+        let tolerance = 1.0e-3 * expected_acceleration.magnitude();
+
+        assert!((accelerations[0] - expected_acceleration * bodies[1].mass).magnitude() < tolerance * bodies[1].mass);
+        assert!((accelerations[1] + expected_acceleration * bodies[0].mass).magnitude() < tolerance * bodies[0].mass);
+    }
+
+    #[test]
+    fn momentum() {
+        for &boundary_condition in &[BoundaryCondition::Periodic, BoundaryCondition::Isolated] {
+            let mut universe = ParticleMeshUniverse::new(&get_random_bodies(100), 64, 4096.0, boundary_condition);
+            let momentum_0 = universe.get_momentum();
+
+            for _ in 0..100 {
+                universe.advance(0.1);
+            }
+
+            assert!((universe.get_momentum() - momentum_0).magnitude() < 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn periodic_wrap() {
+        let bodies = [Body::new(1.0, Vector2::new(15.0, -15.0), Vector2::new(2.0, -2.0))];
+        let mut universe = ParticleMeshUniverse::new(&bodies, 16, 32.0, BoundaryCondition::Periodic);
+
+        universe.advance(1.0);
+
+        assert!((universe.get_bodies()[0].position - Vector2::new(-15.0, 15.0)).magnitude() < 1.0e-12);
+    }
+}
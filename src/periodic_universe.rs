@@ -0,0 +1,142 @@
+use crate::body::Body;
+use crate::ewald::Ewald;
+use crate::universe::Universe;
+use cgmath::{InnerSpace, Vector2};
+use itertools::Itertools;
+
+/// Leapfrog universe in a periodic square box centered at the origin, where bodies leaving the box on one side enter it
+/// on the other side. Forces include all periodic images by Ewald summation, against a uniform background canceling
+/// the mean density, and the energy includes the interactions of bodies with their own images.
+pub struct PeriodicUniverse {
+    bodies: Vec<Body>,
+    box_size: f64,
+    ewald: Ewald,                           // Pre-calculated.
+    acceleration_buffer: Vec<Vector2<f64>>, // Accelerations of the current positions.
+}
+
+fn compute_accelerations(ewald: &Ewald, bodies: &[Body], accelerations: &mut [Vector2<f64>]) {
+    for a in accelerations.iter_mut() {
+        *a = Vector2 { x: 0.0, y: 0.0 };
+    }
+
+    for [(i, body_i), (j, body_j)] in bodies.iter().enumerate().array_combinations() {
+        let gradient = ewald.gradient(body_j.position - body_i.position);
+
+        accelerations[i] -= gradient * body_j.mass;
+        accelerations[j] += gradient * body_i.mass;
+    }
+}
+
+impl PeriodicUniverse {
+    pub fn new(bodies: &[Body], box_size: f64) -> PeriodicUniverse {
+        let ewald = Ewald::new(box_size, 3.0 / box_size);
+        let mut acceleration_buffer = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        let bodies = bodies
+            .iter()
+            .map(|b| Body {
+                position: ewald.minimum_image(b.position),
+                ..b.clone()
+            })
+            .collect::<Vec<_>>();
+
+        compute_accelerations(&ewald, &bodies, &mut acceleration_buffer);
+
+        PeriodicUniverse {
+            bodies,
+            box_size,
+            ewald,
+            acceleration_buffer,
+        }
+    }
+}
+
+impl Universe for PeriodicUniverse {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+            body.position = self.ewald.minimum_image(body.position + body.velocity * time);
+        }
+
+        compute_accelerations(&self.ewald, &self.bodies, &mut self.acceleration_buffer);
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn get_period(&self) -> Option<f64> {
+        Some(self.box_size)
+    }
+
+    fn get_energy(&self) -> f64 {
+        let mut e: f64 = self.bodies.iter().map(|b| b.mass * b.velocity.magnitude2()).sum();
+
+        e *= 0.5;
+
+        let negative_potential_energy: f64 = self
+            .bodies
+            .iter()
+            .array_combinations()
+            .map(|[b_i, b_j]| b_i.mass * b_j.mass * self.ewald.potential(b_j.position - b_i.position))
+            .sum();
+
+        let negative_self_energy: f64 = self
+            .bodies
+            .iter()
+            .map(|b| 0.5 * b.mass * b.mass * self.ewald.self_potential())
+            .sum();
+
+        e -= negative_potential_energy + negative_self_energy;
+
+        e
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PeriodicUniverse;
+    use crate::basic_universe::BasicUniverse;
+    use crate::test_utilities::get_taylor_bodies;
+    use crate::universe::Universe;
+    use cgmath::InnerSpace;
+
+    #[test]
+    fn conservation() {
+        let mut universe = PeriodicUniverse::new(&get_taylor_bodies(), 100.0);
+        let energy = universe.get_energy();
+        let momentum = universe.get_momentum();
+
+        // Bodies wrap around several times, before two of them come too close for the step size.
+
+        for _ in 0..500 {
+            universe.advance(0.001);
+        }
+
+        assert!((universe.get_energy() - energy).abs() < 1.0e-9 * energy.abs());
+        assert!((universe.get_momentum() - momentum).magnitude() < 1.0e-10);
+
+        for body in universe.get_bodies() {
+            assert!(body.position.x.abs() <= 50.0 && body.position.y.abs() <= 50.0);
+        }
+    }
+
+    #[test]
+    fn large_box() {
+        // In a box much larger than the distances, the energy approaches that of open space, apart from the constant
+        // self energy.
+
+        let bodies = get_taylor_bodies();
+        let universe = PeriodicUniverse::new(&bodies, 1.0e6);
+        let self_energy = -0.5 * universe.ewald.self_potential() * bodies.iter().map(|b| b.mass * b.mass).sum::<f64>();
+        let open_energy = BasicUniverse::new(&bodies).get_energy();
+
+        assert!((universe.get_energy() - self_energy - open_energy).abs() < 1.0e-6 * open_energy);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::body::Body;
+use cgmath::{InnerSpace, Vector2};
+use std::f64::consts::PI;
+
+/// Measures the apsidal precession of the orbit of one body around another, by recording the directions of the
+/// periapsis passages, where the distance between them stops decreasing.
+pub struct ApsidalPrecession {
+    first: usize,
+    second: usize,
+    last_state: Option<(f64, Vector2<f64>, f64)>, // State, time, offset and radial velocity of the last record.
+    periapses: Vec<(f64, f64)>,                   // State, times and unwrapped angles of the periapsis passages.
+}
+
+impl ApsidalPrecession {
+    /// Tracks the orbit of the body with index `second` relative to the body with index `first`.
+    pub fn new(first: usize, second: usize) -> ApsidalPrecession {
+        ApsidalPrecession {
+            first,
+            second,
+            last_state: None,
+            periapses: Vec::new(),
+        }
+    }
+
+    /// Records the bodies at the given time, which should be called at least several times per orbit.
+    pub fn record(&mut self, time: f64, bodies: &[Body]) {
+        let offset = bodies[self.second].position - bodies[self.first].position;
+        let velocity = bodies[self.second].velocity - bodies[self.first].velocity;
+        let radial_velocity = offset.dot(velocity);
+
+        if let Some((last_time, last_offset, last_radial_velocity)) = self.last_state {
+            if last_radial_velocity < 0.0 && radial_velocity >= 0.0 {
+                // Interpolates linearly to where the radial velocity vanishes.
+
+                let t = last_radial_velocity / (last_radial_velocity - radial_velocity);
+                let periapsis = last_offset + (offset - last_offset) * t;
+                let mut angle = periapsis.y.atan2(periapsis.x);
+
+                if let Some(&(_, last_angle)) = self.periapses.last() {
+                    angle += ((last_angle - angle) / (2.0 * PI)).round() * (2.0 * PI);
+                }
+
+                self.periapses.push((last_time + (time - last_time) * t, angle));
+            }
+        }
+
+        self.last_state = Some((time, offset, radial_velocity));
+    }
+
+    /// Times and directions of the recorded periapsis passages, where the directions are unwrapped so that they change
+    /// continuously.
+    pub fn get_periapses(&self) -> &[(f64, f64)] {
+        &self.periapses
+    }
+
+    /// Average angular speed of the periapsis direction between the first and the last periapsis passages, which is
+    /// positive for the direction of the orbit being counterclockwise.
+    pub fn get_precession_rate(&self) -> Option<f64> {
+        match (self.periapses.first(), self.periapses.last()) {
+            (Some(&(first_time, first_angle)), Some(&(last_time, last_angle))) if last_time > first_time => {
+                Some((last_angle - first_angle) / (last_time - first_time))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::body::Body;
+use crate::projection::Projection;
+use crate::universe::Universe;
+use crate::universe3::Universe3;
+
+/// Shows a `Universe3` through a projection as a `Universe`, so its trails can be scheduled and rendered like those of
+/// 2D universes. Positions and velocities are projected onto the canvas, while the mass and the energy are those in 3D
+/// space. Vector diagnostics are of the projected bodies, so use `get_universe` for the 3D ones.
+pub struct ProjectedUniverse<U: Universe3, P: Projection> {
+    universe: U,
+    projection: P,
+    bodies: Vec<Body>, // Buffer.
+}
+
+fn project<U: Universe3, P: Projection>(universe: &U, projection: &P, bodies: &mut Vec<Body>) {
+    bodies.clear();
+
+    bodies.extend(universe.get_bodies().iter().map(|b| {
+        Body::new(
+            b.mass,
+            projection.project(b.position),
+            projection.project_velocity(b.position, b.velocity),
+        )
+    }));
+}
+
+impl<U: Universe3, P: Projection> ProjectedUniverse<U, P> {
+    pub fn new(universe: U, projection: P) -> ProjectedUniverse<U, P> {
+        let mut bodies = Vec::new();
+
+        project(&universe, &projection, &mut bodies);
+
+        ProjectedUniverse {
+            universe,
+            projection,
+            bodies,
+        }
+    }
+
+    pub fn get_universe(&self) -> &U {
+        &self.universe
+    }
+
+    /// Changes the projection, which applies to the positions from now on.
+    pub fn set_projection(&mut self, projection: P) {
+        self.projection = projection;
+
+        project(&self.universe, &self.projection, &mut self.bodies);
+    }
+}
+
+impl<U: Universe3, P: Projection> Universe for ProjectedUniverse<U, P> {
+    fn advance(&mut self, time: f64) {
+        self.universe.advance(time);
+
+        project(&self.universe, &self.projection, &mut self.bodies);
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn get_mass(&self) -> f64 {
+        self.universe.get_mass()
+    }
+
+    fn get_energy(&self) -> f64 {
+        self.universe.get_energy()
+    }
+}
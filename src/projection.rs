@@ -0,0 +1,66 @@
+use cgmath::{Vector2, Vector3};
+
+/// Mapping from 3D space onto the canvas, where y grows downwards.
+pub trait Projection: 'static {
+    fn project(&self, point: Vector3<f64>) -> Vector2<f64>;
+
+    /// Velocity on the canvas of a point moving with the given velocity.
+    fn project_velocity(&self, point: Vector3<f64>, velocity: Vector3<f64>) -> Vector2<f64>;
+}
+
+/// Stereographic projection of a sphere centered at the origin from its south pole onto the tangent plane at its north
+/// pole, with x and y mapped to the canvas x and y. It preserves angles, and scales by 1 at the north pole.
+#[derive(Clone)]
+pub struct StereographicProjection {
+    radius: f64,
+}
+
+impl StereographicProjection {
+    pub fn new(radius: f64) -> StereographicProjection {
+        StereographicProjection { radius }
+    }
+
+    /// Inverse of the projection, returning the point on the sphere and its velocity.
+    pub fn unproject(&self, point: Vector2<f64>, velocity: Vector2<f64>) -> (Vector3<f64>, Vector3<f64>) {
+        let diameter_squared = 4.0 * self.radius * self.radius;
+        let t = diameter_squared / (point.x * point.x + point.y * point.y + diameter_squared);
+        let t_velocity = -2.0 * t * t * (point.x * velocity.x + point.y * velocity.y) / diameter_squared;
+
+        (
+            (point * t).extend(self.radius * (2.0 * t - 1.0)),
+            (velocity * t + point * t_velocity).extend(2.0 * self.radius * t_velocity),
+        )
+    }
+}
+
+impl Projection for StereographicProjection {
+    fn project(&self, point: Vector3<f64>) -> Vector2<f64> {
+        point.truncate() * (2.0 * self.radius / (self.radius + point.z))
+    }
+
+    fn project_velocity(&self, point: Vector3<f64>, velocity: Vector3<f64>) -> Vector2<f64> {
+        let denominator = self.radius + point.z;
+
+        (velocity.truncate() * denominator - point.truncate() * velocity.z)
+            * (2.0 * self.radius / (denominator * denominator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Projection, StereographicProjection};
+    use cgmath::{InnerSpace, Vector2};
+
+    #[test]
+    fn stereographic() {
+        let projection = StereographicProjection::new(5.0);
+        let point = Vector2::new(3.0, -7.0);
+        let velocity = Vector2::new(-2.0, 0.5);
+        let (point3, velocity3) = projection.unproject(point, velocity);
+
+        assert!((point3.magnitude() - 5.0).abs() < 1.0e-12);
+        assert!(point3.dot(velocity3).abs() < 1.0e-12);
+        assert!((projection.project(point3) - point).magnitude() < 1.0e-12);
+        assert!((projection.project_velocity(point3, velocity3) - velocity).magnitude() < 1.0e-12);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::body::Body;
+use crate::forces::pow_negative_one_half;
+use cgmath::{InnerSpace, Vector2};
+
+// Cells with this many bodies are not divided any further.
+const MAX_LEAF_BODIES: usize = 1;
+
+// Prevents infinite subdivision of bodies at the same position.
+const MAX_DEPTH: usize = 48;
+
+struct Node {
+    mass: f64,
+    center_of_mass: Vector2<f64>,
+    center: Vector2<f64>,
+    size: f64,
+    bodies_start: usize, // Range in `QuadTree::body_indices`.
+    bodies_end: usize,   // Range in `QuadTree::body_indices`.
+    is_leaf: bool,
+    next: usize, // Index of the next node after the subtree of this node.
+}
+
+/// Quadtree over body positions, with nodes stored in depth-first order so it can be traversed without a stack.
+pub struct QuadTree {
+    nodes: Vec<Node>,
+    body_indices: Vec<usize>,
+}
+
+impl QuadTree {
+    pub fn new() -> QuadTree {
+        QuadTree {
+            nodes: Vec::new(),
+            body_indices: Vec::new(),
+        }
+    }
+
+    /// Rebuilds the tree from the positions of the bodies, reusing the allocated memory.
+    pub fn build(&mut self, bodies: &[Body]) {
+        self.nodes.clear();
+        self.body_indices.clear();
+        self.body_indices.extend(0..bodies.len());
+
+        if let Some((first, rest)) = bodies.split_first() {
+            let (min, max) = rest.iter().fold((first.position, first.position), |(min, max), b| {
+                (
+                    Vector2::new(min.x.min(b.position.x), min.y.min(b.position.y)),
+                    Vector2::new(max.x.max(b.position.x), max.y.max(b.position.y)),
+                )
+            });
+
+            let size = (max.x - min.x).max(max.y - min.y);
+
+            self.build_node(bodies, (min + max) * 0.5, size, 0, bodies.len(), 0);
+        }
+    }
+
+    fn build_node(&mut self, bodies: &[Body], center: Vector2<f64>, size: f64, start: usize, end: usize, depth: usize) {
+        let node_index = self.nodes.len();
+        let is_leaf = end - start <= MAX_LEAF_BODIES || depth == MAX_DEPTH;
+        let indices = &self.body_indices[start..end];
+        let mass = indices.iter().map(|&i| bodies[i].mass).sum::<f64>();
+
+        let center_of_mass = indices
+            .iter()
+            .map(|&i| bodies[i].position * bodies[i].mass)
+            .sum::<Vector2<f64>>()
+            / mass;
+
+        self.nodes.push(Node {
+            mass,
+            center_of_mass,
+            center,
+            size,
+            bodies_start: start,
+            bodies_end: end,
+            is_leaf,
+            next: 0,
+        });
+
+        if !is_leaf {
+            // Partition the bodies into quadrants: first by y, then each half by x.
+
+            let indices = &mut self.body_indices[start..end];
+            let middle = start + partition(indices, |i| bodies[i].position.y < center.y);
+            let lower_middle = start
+                + partition(&mut self.body_indices[start..middle], |i| {
+                    bodies[i].position.x < center.x
+                });
+            let upper_middle =
+                middle + partition(&mut self.body_indices[middle..end], |i| bodies[i].position.x < center.x);
+            let half_size = size * 0.5;
+            let quarter_size = size * 0.25;
+
+            let quadrants = [
+                (start, lower_middle, Vector2::new(-quarter_size, -quarter_size)),
+                (lower_middle, middle, Vector2::new(quarter_size, -quarter_size)),
+                (middle, upper_middle, Vector2::new(-quarter_size, quarter_size)),
+                (upper_middle, end, Vector2::new(quarter_size, quarter_size)),
+            ];
+
+            for &(quadrant_start, quadrant_end, offset) in &quadrants {
+                if quadrant_start != quadrant_end {
+                    self.build_node(
+                        bodies,
+                        center + offset,
+                        half_size,
+                        quadrant_start,
+                        quadrant_end,
+                        depth + 1,
+                    );
+                }
+            }
+        }
+
+        self.nodes[node_index].next = self.nodes.len();
+    }
+
+    /// Computes the acceleration of the body with the given index. A cell not containing the body is treated as a point
+    /// mass at its center of mass if its size divided by its distance to the body is less than `opening_angle`.
+    pub fn compute_acceleration(&self, bodies: &[Body], index: usize, opening_angle: f64) -> Vector2<f64> {
+        let position = bodies[index].position;
+        let opening_angle_squared = opening_angle * opening_angle;
+        let mut acceleration = Vector2 { x: 0.0, y: 0.0 };
+        let mut node_index = 0;
+
+        while let Some(node) = self.nodes.get(node_index) {
+            let distance = node.center_of_mass - position;
+            let distance_squared = distance.magnitude2();
+
+            let offset = position - node.center;
+            let half_size = node.size * 0.5;
+            let is_outside = offset.x.abs().max(offset.y.abs()) > half_size;
+
+            if is_outside && node.size * node.size < opening_angle_squared * distance_squared {
+                acceleration += distance * (node.mass * pow_negative_one_half(distance_squared));
+                node_index = node.next;
+            } else if node.is_leaf {
+                for &i in &self.body_indices[node.bodies_start..node.bodies_end] {
+                    if i != index {
+                        let distance = bodies[i].position - position;
+
+                        acceleration += distance * (bodies[i].mass * pow_negative_one_half(distance.magnitude2()));
+                    }
+                }
+
+                node_index = node.next;
+            } else {
+                node_index += 1;
+            }
+        }
+
+        acceleration
+    }
+}
+
+// Moves the elements satisfying the predicate to the front, and returns the number of them.
+fn partition<F: Fn(usize) -> bool>(indices: &mut [usize], predicate: F) -> usize {
+    let mut count = 0;
+
+    for i in 0..indices.len() {
+        if predicate(indices[i]) {
+            indices.swap(i, count);
+            count += 1;
+        }
+    }
+
+    count
+}
@@ -0,0 +1,245 @@
+use crate::body::Body;
+use crate::force_law::ForceLaw;
+use crate::forces::{compute_pairwise_accelerations_except, coulomb_coupling};
+use crate::kepler::kepler_drift;
+use crate::softening::Softening;
+use crate::universe::Universe;
+use cgmath::{InnerSpace, Vector2};
+use itertools::Itertools;
+
+/// Leapfrog universe, which moves the closest pair of bodies within the regularization radius on its exact Kepler orbit
+/// in Levi-Civita coordinates, and treats the forces of all other bodies on the pair as perturbations. Close encounters
+/// of an isolated pair, even collisions, are followed exactly up to rounding errors at any step size. The error of a
+/// perturbed pair grows with the perturbations and the step size, as for leapfrog, but not with the closeness of the
+/// encounter.
+pub struct RegularizedUniverse {
+    bodies: Vec<Body>,
+    regularization_radius: f64,
+    pair: Option<(usize, usize)>,           // Pair of the accelerations.
+    acceleration_buffer: Vec<Vector2<f64>>, // Accelerations of the current positions.
+}
+
+// Computes the accelerations of the bodies without the interaction of the given pair.
+fn compute_accelerations(bodies: &[Body], pair: Option<(usize, usize)>, accelerations: &mut [Vector2<f64>]) {
+    compute_pairwise_accelerations_except(
+        bodies,
+        ForceLaw::InverseSquare,
+        Softening::None,
+        |i, j| pair == Some((i, j)),
+        accelerations,
+    );
+}
+
+impl RegularizedUniverse {
+    pub fn new(bodies: &[Body], regularization_radius: f64) -> RegularizedUniverse {
+        let mut acceleration_buffer = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        compute_accelerations(bodies, None, &mut acceleration_buffer);
+
+        RegularizedUniverse {
+            bodies: bodies.to_vec(),
+            regularization_radius,
+            pair: None,
+            acceleration_buffer,
+        }
+    }
+
+    // The closest pair of bodies within the regularization radius.
+    fn find_pair(&self) -> Option<(usize, usize)> {
+        self.bodies
+            .iter()
+            .enumerate()
+            .array_combinations()
+            .map(|[(i, body_i), (j, body_j)]| ((i, j), (body_j.position - body_i.position).magnitude2()))
+            .filter(|&(_, distance_squared)| distance_squared < self.regularization_radius * self.regularization_radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(pair, _)| pair)
+    }
+}
+
+impl Universe for RegularizedUniverse {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        // The pair is kept for the whole step, so the splitting stays time symmetric.
+
+        let pair = self.find_pair();
+
+        if pair != self.pair {
+            self.pair = pair;
+
+            compute_accelerations(&self.bodies, pair, &mut self.acceleration_buffer);
+        }
+
+        // Kick and drift, where the pair drifts on its Kepler orbit around its center of mass.
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+
+        for (k, body) in self.bodies.iter_mut().enumerate() {
+            if pair.is_none_or(|(i, j)| k != i && k != j) {
+                body.position += body.velocity * time;
+            }
+        }
+
+        if let Some((i, j)) = pair {
+            let (mass_i, mass_j) = (self.bodies[i].mass, self.bodies[j].mass);
+            let mass = mass_i + mass_j;
+
+            // Coulomb forces within the pair change the strength of its Kepler potential, which becomes repulsive for
+            // like charges overcoming gravity.
+
+            let charge_product = coulomb_coupling(&self.bodies[i], &self.bodies[j]);
+
+            let mu = if charge_product == 0.0 {
+                mass
+            } else {
+                mass - charge_product * (mass_i.recip() + mass_j.recip())
+            };
+
+            let center_of_mass = (self.bodies[i].position * mass_i + self.bodies[j].position * mass_j) / mass
+                + (self.bodies[i].velocity * mass_i + self.bodies[j].velocity * mass_j) / mass * time;
+
+            let velocity = (self.bodies[i].velocity * mass_i + self.bodies[j].velocity * mass_j) / mass;
+
+            let (relative_position, relative_velocity) = kepler_drift(
+                mu,
+                self.bodies[j].position - self.bodies[i].position,
+                self.bodies[j].velocity - self.bodies[i].velocity,
+                time,
+            );
+
+            self.bodies[i].position = center_of_mass - relative_position * (mass_j / mass);
+            self.bodies[j].position = center_of_mass + relative_position * (mass_i / mass);
+            self.bodies[i].velocity = velocity - relative_velocity * (mass_j / mass);
+            self.bodies[j].velocity = velocity + relative_velocity * (mass_i / mass);
+        }
+
+        // Kick again using the accelerations of the new positions, which are kept for the next step.
+
+        compute_accelerations(&self.bodies, pair, &mut self.acceleration_buffer);
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RegularizedUniverse;
+    use crate::body::Body;
+    use crate::leapfrog_universe::LeapfrogUniverse;
+    use crate::test_utilities::{
+        check_angular_momentum, check_binary_orbit, check_center_of_mass, check_energy, check_momentum,
+        check_reversibility, check_trajectory, get_binary_bodies, get_taylor_bodies,
+    };
+    use crate::universe::Universe;
+    use cgmath::Vector2;
+
+    fn get_taylor_universe() -> RegularizedUniverse {
+        RegularizedUniverse::new(&get_taylor_bodies(), 30.0)
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(get_taylor_universe(), 0.001, 1000, 1.0e-4);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        check_center_of_mass(get_taylor_universe(), 0.001, 1000, 1.0e-23);
+    }
+
+    #[test]
+    fn momentum() {
+        check_momentum(get_taylor_universe(), 0.001, 1000, 1.0e-18);
+    }
+
+    #[test]
+    fn angular_momentun() {
+        check_angular_momentum(get_taylor_universe(), 0.001, 1000, 1.0e-8);
+    }
+
+    #[test]
+    fn trajectory() {
+        check_trajectory(get_taylor_universe(), 0.001, 1000, 1.0e-15);
+    }
+
+    #[test]
+    fn charges() {
+        // Like and unlike charges overcoming gravity, so regularized pairs may repel each other.
+
+        let bodies = get_taylor_bodies()
+            .into_iter()
+            .enumerate()
+            .map(|(i, b)| {
+                let charge = 2.0 * b.mass;
+
+                b.with_charge(if i % 2 == 0 { charge } else { -charge })
+            })
+            .collect::<Vec<_>>();
+
+        check_trajectory(RegularizedUniverse::new(&bodies, 30.0), 0.001, 1000, 1.0e-14);
+
+        // An isolated repelling pair is solved exactly, even with large steps.
+
+        let bodies = [
+            Body::new(1.0, Vector2::new(-2.0, 0.0), Vector2::new(1.0, 0.2)).with_charge(2.0),
+            Body::new(1.0, Vector2::new(2.0, 0.0), Vector2::new(-1.0, -0.2)).with_charge(2.0),
+        ];
+
+        check_trajectory(RegularizedUniverse::new(&bodies, 10.0), 0.5, 8, 1.0e-20);
+    }
+
+    #[test]
+    fn reversibility() {
+        // Through the periapsis of an eccentric binary, which is regularized forward and backward.
+
+        check_reversibility(
+            RegularizedUniverse::new(&get_binary_bodies(0.999), 2.0),
+            0.01,
+            300,
+            1.0e-24,
+        );
+    }
+
+    #[test]
+    fn close_encounter() {
+        // An isolated pair is solved exactly, even with a few steps per orbit.
+
+        let mut universe = RegularizedUniverse::new(&get_binary_bodies(0.999), 2.0);
+
+        check_binary_orbit(&mut universe, 10, 1.0e-9, 1.0e-18);
+    }
+
+    #[test]
+    fn perturbed_close_encounter() {
+        // A light body far from a highly eccentric binary, where leapfrog loses the energy at the periapsis.
+
+        let mut bodies = get_binary_bodies(0.999);
+
+        bodies.push(Body::new(0.01, Vector2::new(0.0, 10.0), Vector2::new(0.4, 0.0)));
+
+        let mut universe = RegularizedUniverse::new(&bodies, 2.0);
+        let mut leapfrog_universe = LeapfrogUniverse::new(&bodies);
+        let e0 = universe.get_energy();
+
+        for _ in 0..1000 {
+            universe.advance(0.01);
+            leapfrog_universe.advance(0.01);
+        }
+
+        assert!(((universe.get_energy() - e0) / e0).abs() < 1.0e-6);
+        assert!(((leapfrog_universe.get_energy() - e0) / e0).abs() > 1.0e-2);
+    }
+}
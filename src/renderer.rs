@@ -2,4 +2,10 @@ use cgmath::Vector2;
 
 pub trait Renderer: 'static {
     fn render(&mut self, position_histories: &[Vec<Vector2<f64>>]);
+
+    /// Forgets the body with the given index after it has been removed from the universe.
+    fn remove_body(&mut self, _index: usize) {}
+
+    /// Renders the current positions of test particles, which have no histories.
+    fn render_test_particles(&mut self, _positions: &[Vector2<f64>]) {}
 }
@@ -0,0 +1,116 @@
+use crate::forces::pow_negative_one_half;
+
+/// Smoothing of the pairwise interaction at small distances, which bounds the forces of close encounters.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Softening {
+    /// Newtonian interaction.
+    #[default]
+    None,
+    /// Potential -m / √(r² + ε²) with the given softening length ε.
+    Plummer(f64),
+    /// Potential of a mass spread by the cubic spline kernel of Monaghan and Lattanzio, which is Newtonian beyond the
+    /// given kernel radius h. The Plummer equivalent softening length is about h / 2.8.
+    Spline(f64),
+}
+
+impl Softening {
+    /// The softened counterpart of 1 / r, so the potential energy of two bodies is -m₁ m₂ `inverse_distance(r²)`.
+    pub fn inverse_distance(self, distance_squared: f64) -> f64 {
+        match self {
+            Softening::None => distance_squared.sqrt().recip(),
+            Softening::Plummer(length) => (distance_squared + length * length).sqrt().recip(),
+            Softening::Spline(radius) => {
+                let distance = distance_squared.sqrt();
+
+                if distance >= radius {
+                    distance.recip()
+                } else {
+                    let u = distance / radius;
+
+                    let w = if u < 0.5 {
+                        2.8 - u * u * (16.0 / 3.0 + u * u * (6.4 * u - 9.6))
+                    } else {
+                        3.2 - 1.0 / (15.0 * u) - u * u * (32.0 / 3.0 + u * (-16.0 + u * (9.6 - 32.0 / 15.0 * u)))
+                    };
+
+                    w / radius
+                }
+            }
+        }
+    }
+
+    /// The softened counterpart of 1 / r³, so the acceleration of a body towards a body with mass m at distance d is
+    /// m d `inverse_distance_cubed(|d|²)`.
+    pub fn inverse_distance_cubed(self, distance_squared: f64) -> f64 {
+        match self {
+            Softening::None => pow_negative_one_half(distance_squared),
+            Softening::Plummer(length) => pow_negative_one_half(distance_squared + length * length),
+            Softening::Spline(radius) => {
+                if distance_squared >= radius * radius {
+                    pow_negative_one_half(distance_squared)
+                } else {
+                    let u = distance_squared.sqrt() / radius;
+
+                    let w = if u < 0.5 {
+                        32.0 / 3.0 + u * u * (32.0 * u - 38.4)
+                    } else {
+                        64.0 / 3.0 - 48.0 * u + 38.4 * u * u - 32.0 / 3.0 * u * u * u - 1.0 / (15.0 * u * u * u)
+                    };
+
+                    w / (radius * radius * radius)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Softening;
+
+    #[test]
+    fn force_is_potential_gradient() {
+        for &softening in &[Softening::None, Softening::Plummer(0.3), Softening::Spline(0.8)] {
+            let mut r: f64 = 0.01;
+
+            while r < 2.0 {
+                let h = 1.0e-6;
+                let potential = |r: f64| -softening.inverse_distance(r * r);
+                let gradient = (potential(r + h) - potential(r - h)) / (2.0 * h);
+
+                assert!(
+                    (gradient - r * softening.inverse_distance_cubed(r * r)).abs() < 1.0e-6 * gradient.abs().max(1.0)
+                );
+
+                r += 0.01;
+            }
+        }
+    }
+
+    #[test]
+    fn spline() {
+        let softening = Softening::Spline(0.8);
+
+        // Continuous where the kernel pieces meet.
+
+        for &r in &[0.4, 0.8] {
+            let (below, above) = (r * (1.0 - 1.0e-12), r * (1.0 + 1.0e-12));
+
+            assert!(
+                (softening.inverse_distance(below * below) - softening.inverse_distance(above * above)).abs() < 1.0e-9
+            );
+
+            assert!(
+                (softening.inverse_distance_cubed(below * below) - softening.inverse_distance_cubed(above * above))
+                    .abs()
+                    < 1.0e-9
+            );
+        }
+
+        // Finite at zero distance, and Newtonian beyond the kernel radius.
+
+        assert!((softening.inverse_distance(0.0) - 2.8 / 0.8).abs() < 1.0e-12);
+        assert!((softening.inverse_distance(1.0) - 1.0).abs() < 1.0e-15);
+        assert!((softening.inverse_distance_cubed(1.0) - 1.0).abs() < 1.0e-15);
+    }
+}
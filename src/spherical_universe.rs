@@ -0,0 +1,180 @@
+use crate::body3::Body3;
+use crate::universe3::Universe3;
+use cgmath::{InnerSpace, Vector3};
+use itertools::Itertools;
+
+/// Universe on the surface of a sphere centered at the origin, which is a closed 2D space with constant curvature. The
+/// interaction of two bodies at angle θ apart has the potential -m₁ m₂ cot(θ) / R, which approaches -m₁ m₂ / r for
+/// distances much smaller than the radius R, and bodies move along great circles between leapfrog kicks. The force
+/// pulls along the shorter great circle at every distance, and diverges at antipodes as well as at coincident
+/// positions.
+///
+/// Bodies are given with positions on the sphere and velocities tangent to it. The angular momentum is conserved,
+/// while the momentum and the center of mass have no meaning in curved space.
+pub struct SphericalUniverse {
+    bodies: Vec<Body3>,
+    radius: f64,
+    acceleration_buffer: Vec<Vector3<f64>>, // Accelerations of the current positions.
+}
+
+fn compute_accelerations(bodies: &[Body3], radius: f64, accelerations: &mut [Vector3<f64>]) {
+    let radius_squared = radius * radius;
+
+    for a in accelerations.iter_mut() {
+        *a = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+    }
+
+    // The tangential gradient of the potential has magnitude m₁ m₂ / (R sin(θ))², pointing along the great circle.
+
+    for [(i, body_i), (j, body_j)] in bodies.iter().enumerate().array_combinations() {
+        let cos = body_i.position.dot(body_j.position) / radius_squared;
+        let sin = body_i.position.cross(body_j.position).magnitude() / radius_squared;
+        let scale = (radius * sin).powi(3).recip();
+
+        accelerations[i] += (body_j.position - body_i.position * cos) * (body_j.mass * scale);
+        accelerations[j] += (body_i.position - body_j.position * cos) * (body_i.mass * scale);
+    }
+}
+
+impl SphericalUniverse {
+    pub fn new(bodies: &[Body3], radius: f64) -> SphericalUniverse {
+        let mut acceleration_buffer = vec![Vector3 { x: 0.0, y: 0.0, z: 0.0 }; bodies.len()];
+
+        compute_accelerations(bodies, radius, &mut acceleration_buffer);
+
+        SphericalUniverse {
+            bodies: bodies.to_vec(),
+            radius,
+            acceleration_buffer,
+        }
+    }
+
+    pub fn get_radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl Universe3 for SphericalUniverse {
+    fn advance(&mut self, time: f64) {
+        let half_time = time * 0.5;
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+
+            // Move along the great circle, and remove rounding errors off the sphere.
+
+            let speed = body.velocity.magnitude();
+
+            if speed > 0.0 {
+                let angular_speed = speed / self.radius;
+                let (sin, cos) = (angular_speed * time).sin_cos();
+                let position = body.position * cos + body.velocity * (sin / angular_speed);
+                let velocity = body.velocity * cos - body.position * (sin * angular_speed);
+
+                body.position = position * (self.radius / position.magnitude());
+                body.velocity = velocity - body.position * (velocity.dot(body.position) / (self.radius * self.radius));
+            }
+        }
+
+        compute_accelerations(&self.bodies, self.radius, &mut self.acceleration_buffer);
+
+        for (body, acceleration) in self.bodies.iter_mut().zip(&self.acceleration_buffer) {
+            body.velocity += acceleration * half_time;
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body3] {
+        &self.bodies
+    }
+
+    fn get_energy(&self) -> f64 {
+        let radius_squared = self.radius * self.radius;
+
+        let mut e: f64 = self.bodies.iter().map(|b| b.mass * b.velocity.magnitude2()).sum();
+
+        e *= 0.5;
+
+        let negative_potential_energy: f64 = self
+            .bodies
+            .iter()
+            .array_combinations()
+            .map(|[b_i, b_j]| {
+                let cos = b_i.position.dot(b_j.position) / radius_squared;
+                let sin = b_i.position.cross(b_j.position).magnitude() / radius_squared;
+
+                b_i.mass * b_j.mass * cos / (sin * self.radius)
+            })
+            .sum();
+
+        e -= negative_potential_energy;
+
+        e
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SphericalUniverse;
+    use crate::body3::Body3;
+    use crate::leapfrog_universe::LeapfrogUniverse;
+    use crate::projection::{Projection, StereographicProjection};
+    use crate::test_utilities::get_taylor_bodies;
+    use crate::universe::Universe;
+    use crate::universe3::Universe3;
+    use cgmath::InnerSpace;
+
+    fn get_spherical_bodies(radius: f64) -> Vec<Body3> {
+        let projection = StereographicProjection::new(radius);
+
+        get_taylor_bodies()
+            .iter()
+            .map(|b| {
+                let (position, velocity) = projection.unproject(b.position, b.velocity);
+
+                Body3::new(b.mass, position, velocity)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn conservation() {
+        let mut universe = SphericalUniverse::new(&get_spherical_bodies(50.0), 50.0);
+        let energy = universe.get_energy();
+        let angular_momentum = universe.get_angular_momentum();
+
+        for _ in 0..1000 {
+            universe.advance(0.001);
+        }
+
+        assert!((universe.get_energy() - energy).abs() < 1.0e-4 * energy.abs());
+        assert!(
+            (universe.get_angular_momentum() - angular_momentum).magnitude() < 1.0e-9 * angular_momentum.magnitude()
+        );
+
+        for body in universe.get_bodies() {
+            assert!((body.position.magnitude() - 50.0).abs() < 1.0e-12);
+            assert!(body.position.dot(body.velocity).abs() < 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn flat_limit() {
+        // On a large sphere, bodies near the north pole move as in the plane.
+
+        let radius = 1.0e8;
+        let projection = StereographicProjection::new(radius);
+        let mut universe = SphericalUniverse::new(&get_spherical_bodies(radius), radius);
+        let mut flat_universe = LeapfrogUniverse::new(&get_taylor_bodies());
+
+        assert!((universe.get_energy() - flat_universe.get_energy()).abs() < 1.0e-6 * flat_universe.get_energy());
+
+        for _ in 0..1000 {
+            universe.advance(0.001);
+            flat_universe.advance(0.001);
+        }
+
+        for (body, flat_body) in universe.get_bodies().iter().zip(flat_universe.get_bodies()) {
+            assert!((projection.project(body.position) - flat_body.position).magnitude() < 1.0e-6);
+        }
+    }
+}
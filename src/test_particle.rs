@@ -0,0 +1,14 @@
+use cgmath::Vector2;
+
+/// Massless particle, which is attracted by bodies but does not attract anything.
+#[derive(Clone)]
+pub struct TestParticle {
+    pub position: Vector2<f64>,
+    pub velocity: Vector2<f64>,
+}
+
+impl TestParticle {
+    pub fn new(position: Vector2<f64>, velocity: Vector2<f64>) -> TestParticle {
+        TestParticle { position, velocity }
+    }
+}
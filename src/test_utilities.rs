@@ -0,0 +1,167 @@
+use crate::body::Body;
+use crate::bulirsch_stoer_universe::BulirschStoerUniverse;
+use crate::distributions::{Circle, Reciprocal};
+use crate::universe::Universe;
+use cgmath::{InnerSpace, Vector2};
+use rand::distributions::Distribution;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::f64::consts::{PI, SQRT_2};
+
+pub fn get_taylor_bodies() -> Vec<Body> {
+    vec![
+        Body::new(2.0, Vector2::new(3.0, 5.0), Vector2::new(7.0, 11.0)),
+        Body::new(13.0, Vector2::new(19.0, 19.0), Vector2::new(23.0, 29.0)),
+        Body::new(31.0, Vector2::new(37.0, 41.0), Vector2::new(43.0, 47.0)),
+        Body::new(53.0, Vector2::new(59.0, 61.0), Vector2::new(67.0, 71.0)),
+    ]
+}
+
+pub fn check_energy<U: Universe>(mut universe: U, step_size: f64, steps: usize, tolerance: f64) {
+    let e0 = universe.get_energy();
+
+    let expected_energy = 18821413.0 / 58.0
+        - f64::sqrt(2699449.0 / 884.0)
+        - f64::sqrt(162409.0 / 808.0)
+        - f64::sqrt(2809.0 / 1568.0)
+        - f64::sqrt(961.0 / 613.0)
+        - f64::sqrt(169.0 / 113.0);
+
+    assert!((e0 - expected_energy).abs() < 1.0e-10);
+
+    for _ in 0..steps {
+        universe.advance(step_size);
+    }
+
+    let e1 = universe.get_energy();
+
+    assert!((e1 - expected_energy).abs() < tolerance);
+}
+
+pub fn check_center_of_mass<U: Universe>(mut universe: U, step_size: f64, steps: usize, tolerance: f64) {
+    let momentum_0 = universe.get_momentum();
+
+    let center_of_mass_0 = universe.get_center_of_mass();
+    let expected_center_of_mass_0 = Vector2::new(503.0 / 11.0, 529.0 / 11.0);
+
+    assert_eq!(center_of_mass_0, expected_center_of_mass_0);
+
+    for _ in 0..steps {
+        universe.advance(step_size);
+    }
+
+    let center_of_mass_1 = universe.get_center_of_mass();
+    let expected_center_of_mass_1 = expected_center_of_mass_0 + momentum_0 / universe.get_mass();
+
+    assert!((center_of_mass_1 - expected_center_of_mass_1).magnitude2() < tolerance);
+}
+
+pub fn check_momentum<U: Universe>(mut universe: U, step_size: f64, steps: usize, tolerance: f64) {
+    let m0 = universe.get_momentum();
+
+    let expected_momentum = Vector2::new(5197.0, 5619.0);
+
+    assert_eq!(m0, expected_momentum);
+
+    for _ in 0..steps {
+        universe.advance(step_size);
+    }
+
+    let m1 = universe.get_momentum();
+
+    assert!((m1 - expected_momentum).magnitude2() < tolerance);
+}
+
+pub fn check_angular_momentum<U: Universe>(mut universe: U, step_size: f64, steps: usize, tolerance: f64) {
+    let m0 = universe.get_angular_momentum();
+
+    let expected_angular_momentum = 6140.0;
+
+    assert_eq!(m0, expected_angular_momentum);
+
+    for _ in 0..steps {
+        universe.advance(step_size);
+    }
+
+    let m1 = universe.get_angular_momentum();
+
+    assert!((m1 - expected_angular_momentum).abs() < tolerance);
+}
+
+/// Advances a universe forward and then backward by the same steps, and checks that the bodies return to their
+/// initial state.
+pub fn check_reversibility<U: Universe>(mut universe: U, step_size: f64, steps: usize, tolerance: f64) {
+    let initial_bodies = universe.get_bodies().to_vec();
+
+    for _ in 0..steps {
+        universe.advance(step_size);
+    }
+
+    for _ in 0..steps {
+        universe.advance(-step_size);
+    }
+
+    for (body, initial_body) in universe.get_bodies().iter().zip(&initial_bodies) {
+        assert!((body.position - initial_body.position).magnitude2() < tolerance);
+        assert!((body.velocity - initial_body.velocity).magnitude2() < tolerance);
+    }
+}
+
+/// Bodies with random masses scattered uniformly in a disk.
+pub fn get_random_bodies(count: usize) -> Vec<Body> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mass_rng = Reciprocal::new(1.0, 1000.0);
+    let position_rng = Circle::new(1000.0);
+    let velocity_rng = Circle::new(10.0);
+
+    (0..count)
+        .map(|_| {
+            Body::new(
+                mass_rng.sample(&mut rng),
+                position_rng.sample(&mut rng),
+                velocity_rng.sample(&mut rng),
+            )
+        })
+        .collect()
+}
+
+/// Compares the positions of a universe against a high precision reference trajectory from the same initial state.
+pub fn check_trajectory<U: Universe>(mut universe: U, step_size: f64, steps: usize, tolerance: f64) {
+    let mut reference = BulirschStoerUniverse::new(universe.get_bodies(), 1.0e-14, 1.0e-14);
+
+    for _ in 0..steps {
+        universe.advance(step_size);
+    }
+
+    reference.advance(step_size * steps as f64);
+
+    for (body, expected_body) in universe.get_bodies().iter().zip(reference.get_bodies()) {
+        assert!((body.position - expected_body.position).magnitude2() < tolerance);
+    }
+}
+
+/// Two bodies with mass 1 at the apoapsis of an orbit with semi-major axis 1, which have a period of π√2.
+pub fn get_binary_bodies(eccentricity: f64) -> Vec<Body> {
+    let distance = 0.5 * (1.0 + eccentricity);
+    let velocity = 0.5 * (2.0 * (1.0 - eccentricity) / (1.0 + eccentricity)).sqrt();
+
+    vec![
+        Body::new(1.0, Vector2::new(-distance, 0.0), Vector2::new(0.0, -velocity)),
+        Body::new(1.0, Vector2::new(distance, 0.0), Vector2::new(0.0, velocity)),
+    ]
+}
+
+/// Runs a universe created from `get_binary_bodies` for one period, and checks the energy and the final position.
+pub fn check_binary_orbit<U: Universe>(universe: &mut U, steps: usize, energy_tolerance: f64, position_tolerance: f64) {
+    let initial_position = universe.get_bodies()[0].position;
+    let e0 = universe.get_energy();
+
+    for _ in 0..steps {
+        universe.advance(PI * SQRT_2 / steps as f64);
+    }
+
+    let e1 = universe.get_energy();
+
+    assert!(((e1 - e0) / e0).abs() < energy_tolerance);
+    assert!((universe.get_bodies()[0].position - initial_position).magnitude2() < position_tolerance);
+}
@@ -1,30 +1,112 @@
 use crate::body::Body;
+use crate::external_field::ExternalField;
+use crate::force_law::ForceLaw;
+use crate::forces::coulomb_coupling;
+use crate::softening::Softening;
+use crate::test_particle::TestParticle;
 use crate::utilities::cross;
 use cgmath::{InnerSpace, Vector2};
 use itertools::Itertools;
 
 pub trait Universe: 'static {
+    /// Moves the universe forward by the given time, or backward if it is negative. Time-symmetric integrators retrace
+    /// their steps backward, up to rounding errors.
     fn advance(&mut self, time: f64);
     fn get_bodies(&self) -> &[Body];
 
+    /// Indices of the bodies removed by the last call to `advance`, relative to the bodies before the call, in
+    /// ascending order. The remaining bodies keep their order.
+    fn get_removed_bodies(&self) -> &[usize] {
+        &[]
+    }
+
+    /// Massless particles moving with the bodies, which do not count towards any of the statistics.
+    fn get_test_particles(&self) -> &[TestParticle] {
+        &[]
+    }
+
+    /// Side of the square box centered at the origin that space repeats with, if it is periodic.
+    fn get_period(&self) -> Option<f64> {
+        None
+    }
+
+    /// Fixed fields acting on bodies and test particles in addition to the pairwise interaction, which the energy is
+    /// computed with.
+    fn get_external_fields(&self) -> &[ExternalField] {
+        &[]
+    }
+
+    /// Energy carried away by gravitational waves since the start, which is not included in `get_energy`.
+    fn get_radiated_energy(&self) -> f64 {
+        0.0
+    }
+
+    /// Energy taken out of the bodies by drags since the start, which is not included in `get_energy`.
+    fn get_dissipated_energy(&self) -> f64 {
+        0.0
+    }
+
+    /// Time since the start, which time dependent external fields are evaluated at.
+    fn get_time(&self) -> f64 {
+        0.0
+    }
+
+    /// Law of the pairwise interaction, which the energy is computed with.
+    fn get_force_law(&self) -> ForceLaw {
+        ForceLaw::InverseSquare
+    }
+
+    /// Softening of the pairwise interaction, which the energy is computed with.
+    fn get_softening(&self) -> Softening {
+        Softening::None
+    }
+
+    /// Whether charged bodies interact by Coulomb forces, which the energy is computed with.
+    fn has_coulomb_forces(&self) -> bool {
+        false
+    }
+
     fn get_mass(&self) -> f64 {
         self.get_bodies().iter().map(|b| b.mass).sum()
     }
 
     fn get_energy(&self) -> f64 {
         let bodies = self.get_bodies();
+        let force_law = self.get_force_law();
+        let softening = self.get_softening();
+        let coulomb_forces = self.has_coulomb_forces();
 
         let mut e: f64 = bodies.iter().map(|b| b.mass * b.velocity.magnitude2()).sum();
 
         e *= 0.5;
 
-        let negative_potential_energy: f64 = bodies
+        let potential_energy: f64 = bodies
+            .iter()
+            .array_combinations()
+            .map(|[b_i, b_j]| {
+                let distance_squared = (b_j.position - b_i.position).magnitude2();
+
+                let potential = b_i.mass * b_j.mass * force_law.potential(distance_squared, softening);
+
+                if coulomb_forces {
+                    potential + coulomb_coupling(b_i, b_j) * softening.inverse_distance(distance_squared)
+                } else {
+                    potential
+                }
+            })
+            .sum();
+
+        e += potential_energy;
+
+        let time = self.get_time();
+
+        let external_potential_energy: f64 = self
+            .get_external_fields()
             .iter()
-            .tuple_combinations()
-            .map(|(b_i, b_j)| b_i.mass * b_j.mass / (b_j.position - b_i.position).magnitude())
+            .flat_map(|field| bodies.iter().map(move |b| b.mass * field.potential(b.position, time)))
             .sum();
 
-        e -= negative_potential_energy;
+        e += external_potential_energy;
 
         e
     }
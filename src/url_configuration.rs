@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Serialize, Deserialize)]
 pub enum SchedulerType {
     Basic,
+    Galaxy,
+    Ring, // Test particles are only used by the basic and leapfrog universes.
 }
 
 impl Distribution<SchedulerType> for Standard {
@@ -14,9 +16,106 @@ impl Distribution<SchedulerType> for Standard {
     }
 }
 
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum UniverseType {
+    #[default]
+    Basic,
+    Leapfrog,
+    ForestRuth,
+    Yoshida,
+    DormandPrince,
+    Hermite,
+    BulirschStoer,
+    BarnesHut,
+    FastMultipole,
+    ParticleMesh,
+    Leapfrog3,
+    Spherical,
+    Periodic,
+    Regularized,
+    WisdomHolman,
+    GaussRadau,
+}
+
+impl Distribution<UniverseType> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, _rng: &mut R) -> UniverseType {
+        UniverseType::Basic
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum CollisionType {
+    #[default]
+    None,
+    Merge,
+    Bounce,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum ForceLawType {
+    #[default]
+    InverseSquare,
+    InversePower,
+    Yukawa,
+    LennardJones,
+    Harmonic,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum ExternalFieldType {
+    #[default]
+    None,
+    PointMass,
+    LogarithmicHalo,
+    MiyamotoNagai,
+    Uniform,
+    RotatingBar,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum DragType {
+    #[default]
+    None,
+    Linear,
+    Quadratic,
+    DynamicalFriction,
+}
+
+fn default_restitution() -> f64 {
+    1.0
+}
+
+/// Options of the simulation in the query string of the URL. Options that only some universes take are noted at their
+/// fields, and the other universes ignore them.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct UrlConfiguration {
     pub scheduler: SchedulerType,
+    #[serde(default)]
+    pub universe: UniverseType,
+    #[serde(default)]
+    pub softening: f64, // Plummer softening length, or 0 for none. Only used by the basic universe.
+    #[serde(default)]
+    pub force_law: ForceLawType, // Only used by the basic universe.
+    #[serde(default)]
+    pub force_parameter: f64, // Exponent, range, length or stiffness of the force law, or 0 for its default.
+    #[serde(default)]
+    pub external_field: ExternalFieldType, // Only used by the basic and leapfrog universes.
+    #[serde(default)]
+    pub drag: DragType, // Only used by the basic universe.
+    #[serde(default)]
+    pub drag_parameter: f64, // Drag coefficient, or background density of dynamical friction, or 0 for its default.
+    #[serde(default)]
+    pub speed_of_light: f64, // Enables post-Newtonian corrections, or 0 for none. Only used by the basic universe.
+    #[serde(default)]
+    pub radiation_reaction: f64, // Radiation reaction speed of light, or 0 for none. Only used by the basic universe.
+    #[serde(default)]
+    pub charge: f64, // Charge per mass of the bodies, with alternating signs, or 0 for none. Needs Coulomb forces.
+    #[serde(default)]
+    pub collision: CollisionType, // Only used by the basic universe.
+    #[serde(default = "default_restitution")]
+    pub restitution: f64, // Only used for bouncing collisions.
+    #[serde(default)]
+    pub backward: bool, // Plays the simulation backward in time, where merged bodies stay merged.
     pub id: u64,
 }
 
@@ -25,6 +124,19 @@ pub fn random_url_configuration(seed: u64) -> UrlConfiguration {
 
     UrlConfiguration {
         scheduler: rng.gen(),
+        universe: rng.gen(),
+        softening: 0.0,
+        force_law: ForceLawType::InverseSquare,
+        force_parameter: 0.0,
+        external_field: ExternalFieldType::None,
+        drag: DragType::None,
+        drag_parameter: 0.0,
+        speed_of_light: 0.0,
+        radiation_reaction: 0.0,
+        charge: 0.0,
+        collision: CollisionType::None,
+        restitution: default_restitution(),
+        backward: false,
         id: rng.gen(),
     }
 }
@@ -3,3 +3,8 @@ use cgmath::Vector2;
 pub fn cross(u: Vector2<f64>, v: Vector2<f64>) -> f64 {
     u.x * v.y - u.y * v.x
 }
+
+/// The shortest displacement equivalent to `displacement` in a space that repeats with `period` in both directions.
+pub fn minimum_image(displacement: Vector2<f64>, period: f64) -> Vector2<f64> {
+    displacement.map(|x| x - (x / period).round() * period)
+}
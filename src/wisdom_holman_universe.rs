@@ -0,0 +1,280 @@
+use crate::body::Body;
+use crate::force_law::ForceLaw;
+use crate::forces::{compute_pairwise_accelerations_except, coulomb_coupling};
+use crate::kepler::kepler_drift;
+use crate::softening::Softening;
+use crate::universe::Universe;
+use cgmath::Vector2;
+
+/// Wisdom-Holman universe for systems dominated by a central body, which is the most massive body. Bodies move in
+/// democratic heliocentric coordinates, with positions relative to the central body and barycentric velocities. Each
+/// step solves the Kepler orbits around the central body exactly, and treats the interactions between the other bodies
+/// and the motion of the central body as perturbations, so the error is proportional to the ratio of their masses to
+/// the central mass.
+pub struct WisdomHolmanUniverse {
+    bodies: Vec<Body>,
+    central: usize,
+    acceleration_buffer: Vec<Vector2<f64>>, // Interaction accelerations of the current positions.
+}
+
+// Computes the accelerations of the bodies from each other, without the central body.
+fn compute_interaction_accelerations(bodies: &[Body], central: usize, accelerations: &mut [Vector2<f64>]) {
+    compute_pairwise_accelerations_except(
+        bodies,
+        ForceLaw::InverseSquare,
+        Softening::None,
+        |i, j| i == central || j == central,
+        accelerations,
+    );
+}
+
+// Total momentum of the bodies other than the central body with the given barycentric velocities.
+fn compute_momentum(bodies: &[Body], central: usize, velocities: &[Vector2<f64>]) -> Vector2<f64> {
+    bodies
+        .iter()
+        .zip(velocities)
+        .enumerate()
+        .filter(|&(i, _)| i != central)
+        .map(|(_, (body, &velocity))| velocity * body.mass)
+        .sum()
+}
+
+impl WisdomHolmanUniverse {
+    pub fn new(bodies: &[Body]) -> WisdomHolmanUniverse {
+        let central = bodies
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.mass.total_cmp(&b.mass))
+            .map_or(0, |(i, _)| i);
+
+        let mut acceleration_buffer = vec![Vector2 { x: 0.0, y: 0.0 }; bodies.len()];
+
+        compute_interaction_accelerations(bodies, central, &mut acceleration_buffer);
+
+        WisdomHolmanUniverse {
+            bodies: bodies.to_vec(),
+            central,
+            acceleration_buffer,
+        }
+    }
+}
+
+impl Universe for WisdomHolmanUniverse {
+    fn advance(&mut self, time: f64) {
+        if self.bodies.is_empty() {
+            return;
+        }
+
+        let half_time = time * 0.5;
+        let central_mass = self.bodies[self.central].mass;
+        let mass = self.bodies.iter().map(|b| b.mass).sum::<f64>();
+        let velocity = self.bodies.iter().map(|b| b.velocity * b.mass).sum::<Vector2<_>>() / mass;
+
+        // Democratic heliocentric coordinates, where the entries of the central body are unused.
+
+        let central_position = self.bodies[self.central].position;
+
+        let mut positions = self
+            .bodies
+            .iter()
+            .map(|b| b.position - central_position)
+            .collect::<Vec<_>>();
+
+        let mut velocities = self.bodies.iter().map(|b| b.velocity - velocity).collect::<Vec<_>>();
+
+        // Kick with the interactions, and drift with the motion of the central body for half of the step.
+
+        for (velocity, acceleration) in velocities.iter_mut().zip(&self.acceleration_buffer) {
+            *velocity += acceleration * half_time;
+        }
+
+        let central_drift = compute_momentum(&self.bodies, self.central, &velocities) * (half_time / central_mass);
+
+        for position in &mut positions {
+            *position += central_drift;
+        }
+
+        // Kepler orbits around the central body, whose strength includes the Coulomb coupling to the central body.
+
+        for (i, (position, velocity)) in positions.iter_mut().zip(&mut velocities).enumerate() {
+            if i != self.central {
+                let charge_product = coulomb_coupling(&self.bodies[self.central], &self.bodies[i]);
+
+                let mu = if charge_product == 0.0 {
+                    central_mass
+                } else {
+                    central_mass - charge_product / self.bodies[i].mass
+                };
+
+                let (new_position, new_velocity) = kepler_drift(mu, *position, *velocity, time);
+
+                *position = new_position;
+                *velocity = new_velocity;
+            }
+        }
+
+        // Drift with the motion of the central body, and kick again using the interactions of the new positions, which
+        // are kept for the next step.
+
+        let central_drift = compute_momentum(&self.bodies, self.central, &velocities) * (half_time / central_mass);
+
+        for position in &mut positions {
+            *position += central_drift;
+        }
+
+        // Back to barycentric coordinates, where the center of mass moves uniformly. The central body moves against the
+        // displacements of the other bodies, which keeps the rounding errors small.
+
+        let central_displacement = velocity * time
+            - self
+                .bodies
+                .iter()
+                .zip(&positions)
+                .enumerate()
+                .filter(|&(i, _)| i != self.central)
+                .map(|(_, (body, &position))| (position - (body.position - central_position)) * body.mass)
+                .sum::<Vector2<_>>()
+                / mass;
+
+        let new_central_position = central_position + central_displacement;
+
+        for (i, (body, &position)) in self.bodies.iter_mut().zip(&positions).enumerate() {
+            if i != self.central {
+                body.position = new_central_position + position;
+            }
+        }
+
+        self.bodies[self.central].position = new_central_position;
+
+        compute_interaction_accelerations(&self.bodies, self.central, &mut self.acceleration_buffer);
+
+        for (velocity, acceleration) in velocities.iter_mut().zip(&self.acceleration_buffer) {
+            *velocity += acceleration * half_time;
+        }
+
+        let central_velocity = velocity - compute_momentum(&self.bodies, self.central, &velocities) / central_mass;
+
+        for (i, (body, &relative_velocity)) in self.bodies.iter_mut().zip(&velocities).enumerate() {
+            body.velocity = if i == self.central {
+                central_velocity
+            } else {
+                velocity + relative_velocity
+            };
+        }
+    }
+
+    fn get_bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    fn has_coulomb_forces(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WisdomHolmanUniverse;
+    use crate::body::Body;
+    use crate::bulirsch_stoer_universe::BulirschStoerUniverse;
+    use crate::leapfrog_universe::LeapfrogUniverse;
+    use crate::test_utilities::{
+        check_angular_momentum, check_center_of_mass, check_energy, check_momentum, check_reversibility,
+        check_trajectory, get_taylor_bodies,
+    };
+    use crate::universe::Universe;
+    use cgmath::{InnerSpace, Vector2};
+    use std::f64::consts::PI;
+
+    fn get_taylor_universe() -> WisdomHolmanUniverse {
+        WisdomHolmanUniverse::new(&get_taylor_bodies())
+    }
+
+    // A star with mass 1 and two planets with masses 10⁻³ on circular orbits with radii 1 and 1.6.
+    fn get_planetary_bodies() -> Vec<Body> {
+        let mut bodies = vec![Body::new(1.0, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0))];
+
+        for &(radius, angle) in &[(1.0f64, 0.0f64), (1.6, 2.0)] {
+            let (sin, cos) = angle.sin_cos();
+
+            bodies.push(Body::new(
+                0.001,
+                Vector2::new(cos, sin) * radius,
+                Vector2::new(-sin, cos) * radius.sqrt().recip(),
+            ));
+        }
+
+        bodies
+    }
+
+    #[test]
+    fn energy() {
+        check_energy(get_taylor_universe(), 0.001, 1000, 1.0e-4);
+    }
+
+    #[test]
+    fn center_of_mass() {
+        check_center_of_mass(get_taylor_universe(), 0.001, 1000, 1.0e-23);
+    }
+
+    #[test]
+    fn momentum() {
+        check_momentum(get_taylor_universe(), 0.001, 1000, 1.0e-18);
+    }
+
+    #[test]
+    fn angular_momentun() {
+        check_angular_momentum(get_taylor_universe(), 0.001, 1000, 1.0e-8);
+    }
+
+    #[test]
+    fn reversibility() {
+        check_reversibility(WisdomHolmanUniverse::new(&get_planetary_bodies()), 0.1, 100, 1.0e-24);
+    }
+
+    #[test]
+    fn planetary_system() {
+        // Twenty steps per orbit of the inner planet are enough, where leapfrog drifts away.
+
+        let bodies = get_planetary_bodies();
+        let mut universe = WisdomHolmanUniverse::new(&bodies);
+        let mut leapfrog_universe = LeapfrogUniverse::new(&bodies);
+        let mut reference = BulirschStoerUniverse::new(&bodies, 1.0e-14, 1.0e-14);
+        let e0 = universe.get_energy();
+        let step_size = 2.0 * PI / 20.0;
+
+        for _ in 0..200 {
+            universe.advance(step_size);
+            leapfrog_universe.advance(step_size);
+        }
+
+        reference.advance(step_size * 200.0);
+
+        assert!(((universe.get_energy() - e0) / e0).abs() < 1.0e-6);
+
+        for ((body, leapfrog_body), expected_body) in universe
+            .get_bodies()
+            .iter()
+            .zip(leapfrog_universe.get_bodies())
+            .zip(reference.get_bodies())
+            .skip(1)
+        {
+            assert!((body.position - expected_body.position).magnitude() < 1.0e-3);
+            assert!((leapfrog_body.position - expected_body.position).magnitude() > 1.0e-2);
+        }
+    }
+
+    #[test]
+    fn charges() {
+        // Unlike charges of the star and the planets strengthen the Kepler orbits, and like charges of the planets
+        // weaken their interaction.
+
+        let bodies = get_planetary_bodies()
+            .into_iter()
+            .enumerate()
+            .map(|(i, b)| b.with_charge(if i == 0 { 0.5 } else { -0.0005 }))
+            .collect::<Vec<_>>();
+
+        check_trajectory(WisdomHolmanUniverse::new(&bodies), 2.0 * PI / 20.0, 200, 1.0e-6);
+    }
+}